@@ -0,0 +1,462 @@
+use crate::lexer::{Span, SpannedToken};
+
+// Syntax tree built by the parser. Leaves keep the span of the source they
+// were read from so the formatter can put comments back next to them.
+
+// A top level SQL statement
+#[derive(Debug, PartialEq, Clone)]
+pub enum Statement {
+    Query(Box<Query>),
+    Insert(Insert),
+    Update(Update),
+    Delete(Delete),
+    CreateTable(CreateTable),
+    CreateView(CreateView),
+    // A statement the parser does not understand, kept as its tokens
+    Raw(Vec<SpannedToken>),
+}
+
+// One or more keywords read as a unit, e.g. `GROUP BY` or `LEFT OUTER JOIN`,
+// spelled as in the source and separated by single spaces
+#[derive(Debug, PartialEq, Clone)]
+pub struct Keyword {
+    pub text: String,
+    pub span: Span,
+}
+
+// A clause introduced by a keyword, e.g. `WHERE <expr>`
+#[derive(Debug, PartialEq, Clone)]
+pub struct Clause<T> {
+    pub keyword: Keyword,
+    pub body: T,
+}
+
+// An identifier, verbatim including any quotes
+#[derive(Debug, PartialEq, Clone)]
+pub struct Ident {
+    pub value: String,
+    pub span: Span,
+    // Whether the name is delimited, as a quoted identifier or a string
+    // literal (`AS 'total'`), and so always kept as written
+    pub quoted: bool,
+}
+
+// A possibly qualified name such as `schema.table`
+#[derive(Debug, PartialEq, Clone)]
+pub struct ObjectName(pub Vec<Ident>);
+
+// An alias introduced with or without `AS`, optionally naming columns
+#[derive(Debug, PartialEq, Clone)]
+pub struct Alias {
+    // `AS`, when the alias is introduced with it
+    pub keyword: Option<Keyword>,
+    pub name: Ident,
+    pub columns: Vec<Ident>,
+}
+
+// A full query: optional CTEs, a body and the clauses that apply to the body
+#[derive(Debug, PartialEq, Clone)]
+pub struct Query {
+    pub with: Option<With>,
+    pub body: SetExpr,
+    pub order_by: Option<Clause<Vec<OrderByExpr>>>,
+    pub limit: Option<Clause<Expr>>,
+    pub offset: Option<Clause<Expr>>,
+    pub locks: Vec<Lock>,
+}
+
+// A row locking clause, e.g. `FOR UPDATE OF t SKIP LOCKED`
+#[derive(Debug, PartialEq, Clone)]
+pub struct Lock {
+    // `FOR UPDATE`, `FOR NO KEY UPDATE`, `FOR SHARE` or `FOR KEY SHARE`
+    pub keyword: Keyword,
+    pub of: Option<Clause<Vec<ObjectName>>>,
+    // `NOWAIT` or `SKIP LOCKED`
+    pub wait: Option<Keyword>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct With {
+    // `WITH` or `WITH RECURSIVE`
+    pub keyword: Keyword,
+    pub ctes: Vec<Cte>,
+}
+
+// A common table expression: `name [(columns)] AS (query)`
+#[derive(Debug, PartialEq, Clone)]
+pub struct Cte {
+    pub name: Ident,
+    pub columns: Vec<Ident>,
+    pub query: Clause<Box<Query>>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum SetExpr {
+    Select(Box<Select>),
+    Values(Values),
+    // A parenthesized query, e.g. one side of a UNION
+    Query(Box<Query>),
+    SetOperation {
+        left: Box<SetExpr>,
+        // `UNION`, `UNION ALL`, `EXCEPT`, ...
+        operator: Keyword,
+        right: Box<SetExpr>,
+    },
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Select {
+    pub keyword: Keyword,
+    // `DISTINCT` or `ALL`
+    pub quantifier: Option<Keyword>,
+    // `ON (expr, ...)` after `DISTINCT`
+    pub distinct_on: Option<Clause<Vec<Expr>>>,
+    pub projection: Vec<SelectItem>,
+    pub from: Option<Clause<Vec<TableWithJoins>>>,
+    pub selection: Option<Clause<Expr>>,
+    pub group_by: Option<Clause<Vec<Expr>>>,
+    pub having: Option<Clause<Expr>>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct SelectItem {
+    pub expr: Expr,
+    pub alias: Option<Alias>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct TableWithJoins {
+    pub relation: TableFactor,
+    pub joins: Vec<Join>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum TableFactor {
+    // A named table, or a table function when `args` is present
+    Table {
+        name: ObjectName,
+        args: Option<Vec<Expr>>,
+        alias: Option<Alias>,
+    },
+    // A subquery in FROM, optionally `LATERAL`
+    Derived {
+        lateral: Option<Keyword>,
+        subquery: Box<Query>,
+        alias: Option<Alias>,
+    },
+    // A parenthesized join, e.g. `(a JOIN b ON ...)`
+    NestedJoin {
+        table: Box<TableWithJoins>,
+        alias: Option<Alias>,
+    },
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Join {
+    // `JOIN`, `LEFT OUTER JOIN`, `CROSS APPLY`, ...
+    pub operator: Keyword,
+    pub relation: TableFactor,
+    pub constraint: JoinConstraint,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum JoinConstraint {
+    On(Clause<Expr>),
+    Using(Clause<Vec<Ident>>),
+    None,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct OrderByExpr {
+    pub expr: Expr,
+    // `ASC`/`DESC` followed by `NULLS FIRST`/`NULLS LAST`, as written
+    pub options: Vec<Keyword>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Values {
+    pub keyword: Keyword,
+    pub rows: Vec<Vec<Expr>>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Insert {
+    // `INSERT INTO`, or `INSERT` when INTO is left out
+    pub keyword: Keyword,
+    pub table: ObjectName,
+    pub columns: Vec<Ident>,
+    pub source: Box<Query>,
+    pub on_conflict: Option<OnConflict>,
+    pub returning: Option<Clause<Vec<SelectItem>>>,
+}
+
+// `ON CONFLICT [target] DO NOTHING`, or `DO UPDATE SET ...` instead
+#[derive(Debug, PartialEq, Clone)]
+pub struct OnConflict {
+    pub keyword: Keyword,
+    pub target: ConflictTarget,
+    pub action: ConflictAction,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ConflictTarget {
+    // Columns or expressions of a unique index, e.g. `(a, lower(b))`
+    Columns(Vec<Expr>),
+    // `ON CONSTRAINT name`
+    Constraint(Clause<ObjectName>),
+    None,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ConflictAction {
+    // `DO NOTHING`
+    Nothing(Keyword),
+    Update(Box<ConflictUpdate>),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ConflictUpdate {
+    // `DO UPDATE`
+    pub keyword: Keyword,
+    pub assignments: Clause<Vec<Assignment>>,
+    pub selection: Option<Clause<Expr>>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Update {
+    pub keyword: Keyword,
+    pub table: TableWithJoins,
+    pub assignments: Clause<Vec<Assignment>>,
+    pub from: Option<Clause<Vec<TableWithJoins>>>,
+    pub selection: Option<Clause<Expr>>,
+    pub returning: Option<Clause<Vec<SelectItem>>>,
+}
+
+// `target = value` in an UPDATE's SET clause
+#[derive(Debug, PartialEq, Clone)]
+pub struct Assignment {
+    pub target: ObjectName,
+    pub value: Expr,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Delete {
+    // `DELETE FROM`, or `DELETE` when FROM is left out
+    pub keyword: Keyword,
+    pub table: TableFactor,
+    pub using: Option<Clause<Vec<TableWithJoins>>>,
+    pub selection: Option<Clause<Expr>>,
+    pub returning: Option<Clause<Vec<SelectItem>>>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct CreateTable {
+    // Everything from `CREATE` up to the name, e.g. `CREATE TEMPORARY TABLE IF NOT EXISTS`
+    pub keyword: Keyword,
+    pub name: ObjectName,
+    pub elements: Vec<TableElement>,
+    // `AS query`
+    pub query: Option<Clause<Box<Query>>>,
+    // Trailing table options such as `ENGINE = InnoDB`, kept as tokens
+    pub options: Vec<SpannedToken>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum TableElement {
+    Column(ColumnDef),
+    // A table constraint such as `PRIMARY KEY (id)`, kept as tokens
+    Constraint(Vec<SpannedToken>),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ColumnDef {
+    pub name: Ident,
+    pub data_type: DataType,
+    // Column constraints and defaults, kept as tokens
+    pub options: Vec<SpannedToken>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct CreateView {
+    // Everything from `CREATE` up to the name, e.g. `CREATE OR REPLACE VIEW`
+    pub keyword: Keyword,
+    pub name: ObjectName,
+    pub columns: Vec<Ident>,
+    // `AS query`
+    pub query: Clause<Box<Query>>,
+}
+
+// A type name such as `int`, `double precision` or `numeric(10, 2)`
+#[derive(Debug, PartialEq, Clone)]
+pub struct DataType {
+    pub name: Vec<Ident>,
+    pub args: Vec<Expr>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Literal {
+    pub value: String,
+    pub span: Span,
+}
+
+// An operator, either symbolic (`>=`) or made of keywords (`NOT LIKE`)
+#[derive(Debug, PartialEq, Clone)]
+pub struct Operator {
+    pub text: String,
+    pub span: Span,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Expr {
+    Identifier(ObjectName),
+    // `*` or `qualifier.*`
+    Wildcard {
+        qualifier: Option<ObjectName>,
+        span: Span,
+    },
+    Literal(Literal),
+    // A bind parameter placeholder, e.g. `$1` or `:name`
+    Parameter(Literal),
+    // Keywords used as values, e.g. `NULL`, `TRUE` or `CURRENT_DATE`
+    Keyword(Keyword),
+    // A literal preceded by its type, e.g. `DATE '2024-01-01'`
+    TypedString {
+        data_type: Box<DataType>,
+        value: Literal,
+    },
+    BinaryOp {
+        left: Box<Expr>,
+        op: Operator,
+        right: Box<Expr>,
+    },
+    // Operands joined by operators of one precedence, e.g. `a + b - c` or
+    // `x AND y AND z`, kept as a list so that long chains nest no deeper
+    // than a single operator
+    OperatorChain {
+        first: Box<Expr>,
+        rest: Vec<(Operator, Expr)>,
+    },
+    UnaryOp {
+        op: Operator,
+        expr: Box<Expr>,
+    },
+    Nested(Box<Expr>),
+    Tuple(Vec<Expr>),
+    Function(Box<Function>),
+    Subquery(Box<Query>),
+    // `EXISTS (query)` or `NOT EXISTS (query)`
+    Exists {
+        keyword: Keyword,
+        subquery: Box<Query>,
+    },
+    // `expr [NOT] IN (list)`
+    InList {
+        expr: Box<Expr>,
+        keyword: Keyword,
+        list: Vec<Expr>,
+    },
+    // `expr [NOT] IN (query)`
+    InSubquery {
+        expr: Box<Expr>,
+        keyword: Keyword,
+        subquery: Box<Query>,
+    },
+    // `expr [NOT] BETWEEN low AND high`
+    Between {
+        expr: Box<Expr>,
+        keyword: Keyword,
+        low: Box<Expr>,
+        high: Box<Expr>,
+    },
+    Case(Box<Case>),
+    // `CAST(expr AS type)` and its `TRY_CAST` relatives
+    Cast {
+        keyword: Keyword,
+        expr: Box<Expr>,
+        as_keyword: Keyword,
+        data_type: Box<DataType>,
+    },
+    // `expr::type`
+    DoubleColonCast {
+        expr: Box<Expr>,
+        data_type: Box<DataType>,
+    },
+    // Access to a path inside semi-structured data, e.g. `payload:customer.name`
+    PathAccess {
+        expr: Box<Expr>,
+        path: ObjectName,
+    },
+    // `expr[index]` or `expr[lower:upper]`
+    Subscript {
+        expr: Box<Expr>,
+        subscript: Box<Subscript>,
+    },
+    // `ARRAY[1, 2]`, or `[1, 2]` where the keyword is left out
+    Array {
+        keyword: Option<Keyword>,
+        elements: Vec<Expr>,
+    },
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Subscript {
+    Index(Expr),
+    // An array slice; either bound may be left out, e.g. `[:3]`
+    Slice {
+        lower: Option<Expr>,
+        upper: Option<Expr>,
+    },
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Function {
+    pub name: ObjectName,
+    pub args: FunctionArgs,
+    // `FILTER (WHERE ...)`
+    pub filter: Option<Box<Expr>>,
+    pub over: Option<WindowSpec>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum FunctionArgs {
+    List {
+        // `DISTINCT` or `ALL`
+        quantifier: Option<Keyword>,
+        args: Vec<Expr>,
+        order_by: Vec<OrderByExpr>,
+    },
+    // Arguments in a syntax the parser does not model, e.g. `EXTRACT(YEAR FROM d)`
+    Raw(Vec<SpannedToken>),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum WindowSpec {
+    // `OVER window_name`
+    Named(Ident),
+    // `OVER (PARTITION BY ... ORDER BY ... frame)`
+    Inline {
+        partition_by: Vec<Expr>,
+        order_by: Vec<OrderByExpr>,
+        // Frame clause such as `ROWS BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW`
+        frame: Vec<SpannedToken>,
+    },
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Case {
+    pub keyword: Keyword,
+    // Present for a simple `CASE x WHEN ...`, absent for a searched CASE
+    pub operand: Option<Box<Expr>>,
+    pub conditions: Vec<WhenClause>,
+    pub else_result: Option<Clause<Expr>>,
+    pub end: Keyword,
+}
+
+// `WHEN condition THEN result`
+#[derive(Debug, PartialEq, Clone)]
+pub struct WhenClause {
+    pub when: Keyword,
+    pub condition: Expr,
+    pub then: Keyword,
+    pub result: Expr,
+}
@@ -0,0 +1,221 @@
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+
+use clap::builder::RangedU64ValueParser;
+use clap::{Parser, ValueEnum};
+
+use crate::config::{Config, INDENT_WIDTHS, MAX_LINE_WIDTHS};
+use sql_formatter::{CaseStyle, CommaPosition, IndentStyle, SqlDialect};
+
+// Command line arguments
+#[derive(Debug, Parser)]
+#[command(version, about = "Format SQL files or standard input")]
+pub struct Cli {
+    #[arg(
+        value_name = "FILE",
+        help = "SQL files or directories to format; reads standard input when none are given or for `-`"
+    )]
+    pub files: Vec<PathBuf>,
+
+    #[arg(
+        short,
+        long,
+        conflicts_with = "output",
+        help = "Rewrite the given files in place instead of printing them"
+    )]
+    pub write: bool,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["write", "output"],
+        help = "Report files that are not formatted, with a diff, instead of printing them"
+    )]
+    pub check: bool,
+
+    #[arg(
+        long,
+        value_enum,
+        value_name = "WHEN",
+        default_value_t = ColorChoice::Auto,
+        help = "Color the diffs printed by --check"
+    )]
+    pub color: ColorChoice,
+
+    #[arg(
+        long,
+        value_name = "GLOB",
+        default_value = "*.sql",
+        help = "Format files found in directories that match GLOB"
+    )]
+    pub include: Vec<String>,
+
+    #[arg(
+        long,
+        value_name = "GLOB",
+        help = "Skip files and directories found in directories that match GLOB"
+    )]
+    pub exclude: Vec<String>,
+
+    #[arg(
+        short,
+        long,
+        value_name = "PATH",
+        help = "Write the formatted SQL to PATH instead of standard output"
+    )]
+    pub output: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "NAME",
+        help = "Name to report for standard input in error messages"
+    )]
+    pub stdin_filename: Option<String>,
+
+    #[arg(
+        short,
+        long,
+        help = "Print only the formatted SQL, without the prompt and header"
+    )]
+    pub quiet: bool,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Use this configuration file instead of looking for sqlformat.toml"
+    )]
+    pub config: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_enum,
+        value_name = "DIALECT",
+        help = "SQL dialect of the input [default: generic]"
+    )]
+    pub dialect: Option<SqlDialect>,
+
+    #[arg(
+        long,
+        value_enum,
+        value_name = "STYLE",
+        help = "Indent with tabs or spaces [default: tabs]"
+    )]
+    pub indent_style: Option<IndentStyle>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = width_parser(INDENT_WIDTHS),
+        help = "Spaces per indent level, or the width of a tab [default: 4]"
+    )]
+    pub indent_width: Option<usize>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = width_parser(MAX_LINE_WIDTHS),
+        help = "Maximum line width before wrapping [default: 80]"
+    )]
+    pub max_line_width: Option<usize>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Number of blank lines between statements [default: 1]"
+    )]
+    pub lines_between_statements: Option<usize>,
+
+    #[arg(
+        long,
+        value_enum,
+        value_name = "POSITION",
+        help = "Put list commas at the end or the start of lines [default: trailing]"
+    )]
+    pub comma_position: Option<CommaPosition>,
+
+    #[arg(
+        long,
+        value_enum,
+        value_name = "CASE",
+        help = "Case of keywords [default: upper]"
+    )]
+    pub keyword_case: Option<CaseStyle>,
+
+    #[arg(
+        long,
+        value_enum,
+        value_name = "CASE",
+        help = "Case of data type names [default: preserve]"
+    )]
+    pub data_type_case: Option<CaseStyle>,
+
+    #[arg(
+        long,
+        value_enum,
+        value_name = "CASE",
+        help = "Case of built-in function names [default: preserve]"
+    )]
+    pub function_case: Option<CaseStyle>,
+
+    #[arg(
+        long,
+        value_enum,
+        value_name = "CASE",
+        help = "Case of unquoted identifiers [default: preserve]"
+    )]
+    pub identifier_case: Option<CaseStyle>,
+
+    #[arg(
+        long,
+        value_name = "BOOL",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true",
+        help = "Keep CASE expressions that fit on one line [default: false]"
+    )]
+    pub inline_short_case: Option<bool>,
+
+    #[arg(
+        long,
+        value_name = "BOOL",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true",
+        help = "Format the dollar quoted bodies of LANGUAGE sql functions too [default: false]"
+    )]
+    pub format_function_bodies: Option<bool>,
+}
+
+// Accepts the widths in `range`
+fn width_parser(range: RangeInclusive<usize>) -> RangedU64ValueParser<usize> {
+    RangedU64ValueParser::new().range(*range.start() as u64..=*range.end() as u64)
+}
+
+impl Cli {
+    // Formatting options given on the command line, which take precedence
+    // over configuration files
+    pub fn overrides(&self) -> Config {
+        Config {
+            dialect: self.dialect,
+            indent_style: self.indent_style,
+            indent_width: self.indent_width,
+            max_line_width: self.max_line_width,
+            lines_between_statements: self.lines_between_statements,
+            comma_position: self.comma_position,
+            keyword_case: self.keyword_case,
+            data_type_case: self.data_type_case,
+            function_case: self.function_case,
+            identifier_case: self.identifier_case,
+            inline_short_case: self.inline_short_case,
+            format_function_bodies: self.format_function_bodies,
+        }
+    }
+}
+
+// When to color diff output
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum ColorChoice {
+    // Color when writing to a terminal and `NO_COLOR` is not set
+    Auto,
+    Always,
+    Never,
+}
@@ -0,0 +1,255 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use sql_formatter::{CaseStyle, CommaPosition, FormatOptions, IndentStyle, SqlDialect};
+
+// Name of the configuration file looked up next to and above each input
+pub const CONFIG_FILE_NAME: &str = "sqlformat.toml";
+
+// Widths that can be configured, in columns
+pub const INDENT_WIDTHS: RangeInclusive<usize> = 1..=16;
+pub const MAX_LINE_WIDTHS: RangeInclusive<usize> = 1..=1000;
+
+// Settings read from a configuration file or the command line. Unset fields
+// keep the value from the layer below them.
+#[derive(Debug, Default, PartialEq, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    pub dialect: Option<SqlDialect>,
+    pub indent_style: Option<IndentStyle>,
+    pub indent_width: Option<usize>,
+    pub max_line_width: Option<usize>,
+    pub lines_between_statements: Option<usize>,
+    pub comma_position: Option<CommaPosition>,
+    pub keyword_case: Option<CaseStyle>,
+    pub data_type_case: Option<CaseStyle>,
+    pub function_case: Option<CaseStyle>,
+    pub identifier_case: Option<CaseStyle>,
+    pub inline_short_case: Option<bool>,
+    pub format_function_bodies: Option<bool>,
+}
+
+impl Config {
+    // Checks that the values set are in range
+    fn validate(&self) -> Result<(), String> {
+        let widths: [(&str, Option<usize>, &RangeInclusive<usize>); 2] = [
+            ("indent_width", self.indent_width, &INDENT_WIDTHS),
+            ("max_line_width", self.max_line_width, &MAX_LINE_WIDTHS),
+        ];
+        for (key, value, range) in widths {
+            if let Some(value) = value
+                && !range.contains(&value)
+            {
+                return Err(format!(
+                    "{} must be between {} and {}, not {}",
+                    key,
+                    range.start(),
+                    range.end(),
+                    value
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    // Overrides the fields of `options` that this config sets
+    pub fn apply(&self, options: &mut FormatOptions) {
+        if let Some(dialect) = self.dialect {
+            options.dialect = dialect;
+        }
+        if let Some(style) = self.indent_style {
+            options.indent_style = style;
+        }
+        if let Some(width) = self.indent_width {
+            options.indent_width = width;
+        }
+        if let Some(width) = self.max_line_width {
+            options.max_line_width = width;
+        }
+        if let Some(lines) = self.lines_between_statements {
+            options.lines_between_statements = lines;
+        }
+        if let Some(position) = self.comma_position {
+            options.comma_position = position;
+        }
+        if let Some(case) = self.keyword_case {
+            options.keyword_case = case;
+        }
+        if let Some(case) = self.data_type_case {
+            options.data_type_case = case;
+        }
+        if let Some(case) = self.function_case {
+            options.function_case = case;
+        }
+        if let Some(case) = self.identifier_case {
+            options.identifier_case = case;
+        }
+        if let Some(inline) = self.inline_short_case {
+            options.inline_short_case = inline;
+        }
+        if let Some(format) = self.format_function_bodies {
+            options.format_function_bodies = format;
+        }
+    }
+}
+
+// Error raised when a configuration file cannot be read or is invalid
+#[derive(Debug, PartialEq, Clone)]
+pub struct ConfigError {
+    pub path: PathBuf,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.message)
+    }
+}
+
+// Reads and validates the configuration file at `path`
+pub fn load(path: &Path) -> Result<Config, ConfigError> {
+    let error = |message: String| ConfigError {
+        path: path.to_path_buf(),
+        message,
+    };
+    let text: String = fs::read_to_string(path).map_err(|err| error(err.to_string()))?;
+    let config: Config =
+        toml::from_str(&text).map_err(|err| error(err.to_string().trim_end().to_string()))?;
+    config.validate().map_err(error)?;
+    Ok(config)
+}
+
+// Works out the options for files in a directory: the nearest `sqlformat.toml`
+// in it or one of its ancestors (or the file given with `--config`), with the
+// command line overrides applied on top. Lookups are cached per directory
+// since inputs usually share a few directories.
+pub struct ConfigResolver {
+    explicit: Option<Config>,
+    overrides: Config,
+    cache: HashMap<PathBuf, Option<PathBuf>>,
+}
+
+impl ConfigResolver {
+    pub fn new(explicit: Option<Config>, overrides: Config) -> Self {
+        ConfigResolver {
+            explicit,
+            overrides,
+            cache: HashMap::new(),
+        }
+    }
+
+    pub fn options_for(&mut self, dir: &Path) -> Result<FormatOptions, ConfigError> {
+        let config: Config = if let Some(explicit) = &self.explicit {
+            explicit.clone()
+        } else if let Some(path) = self.find(dir) {
+            load(&path)?
+        } else {
+            Config::default()
+        };
+
+        let mut options: FormatOptions = FormatOptions::default();
+        config.apply(&mut options);
+        self.overrides.apply(&mut options);
+        Ok(options)
+    }
+
+    fn find(&mut self, dir: &Path) -> Option<PathBuf> {
+        let dir: PathBuf = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+        if let Some(found) = self.cache.get(&dir) {
+            return found.clone();
+        }
+
+        let candidate: PathBuf = dir.join(CONFIG_FILE_NAME);
+        let found: Option<PathBuf> = if candidate.is_file() {
+            Some(candidate)
+        } else {
+            dir.parent().and_then(|parent| self.find(parent))
+        };
+        self.cache.insert(dir, found.clone());
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // An empty directory of its own for the test called `name`
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir: PathBuf = std::env::temp_dir().join(format!(
+            "sql-formatter-config-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn uses_the_nearest_config_with_the_overrides_on_top() {
+        let dir: PathBuf = scratch_dir("nearest");
+        fs::create_dir_all(dir.join("a/b")).unwrap();
+        fs::write(
+            dir.join(CONFIG_FILE_NAME),
+            "indent_width = 2\nkeyword_case = \"lower\"",
+        )
+        .unwrap();
+        fs::write(dir.join("a").join(CONFIG_FILE_NAME), "max_line_width = 100").unwrap();
+
+        let overrides: Config = Config {
+            max_line_width: Some(120),
+            ..Config::default()
+        };
+        let mut resolver: ConfigResolver = ConfigResolver::new(None, Config::default());
+        let mut overridden: ConfigResolver = ConfigResolver::new(None, overrides);
+
+        let options: FormatOptions = resolver.options_for(&dir).unwrap();
+        assert_eq!(options.indent_width, 2);
+        assert_eq!(options.keyword_case, CaseStyle::Lower);
+
+        // Only the nearest file applies, not the ones above it
+        let options: FormatOptions = resolver.options_for(&dir.join("a/b")).unwrap();
+        assert_eq!(options.max_line_width, 100);
+        assert_eq!(options.indent_width, 4);
+
+        let options: FormatOptions = overridden.options_for(&dir.join("a/b")).unwrap();
+        assert_eq!(options.max_line_width, 120);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn rejects_unknown_keys_and_widths_out_of_range() {
+        let dir: PathBuf = scratch_dir("invalid");
+        let path: PathBuf = dir.join(CONFIG_FILE_NAME);
+
+        fs::write(&path, "indent = 2").unwrap();
+        assert!(
+            load(&path)
+                .unwrap_err()
+                .message
+                .contains("unknown field `indent`")
+        );
+
+        fs::write(&path, "indent_width = 0").unwrap();
+        assert_eq!(
+            load(&path).unwrap_err(),
+            ConfigError {
+                path: path.clone(),
+                message: String::from("indent_width must be between 1 and 16, not 0"),
+            }
+        );
+
+        fs::write(&path, "max_line_width = 0").unwrap();
+        assert_eq!(
+            load(&path).unwrap_err().message,
+            "max_line_width must be between 1 and 1000, not 0"
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
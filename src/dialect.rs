@@ -0,0 +1,438 @@
+use std::fmt::Debug;
+
+#[cfg(feature = "cli")]
+use clap::ValueEnum;
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+
+use crate::keywords::{self, KeywordSet};
+
+/// The lexical rules of a SQL dialect, consulted by the lexer, the splitter,
+/// the parser and the formatter. Defaults follow standard SQL.
+pub trait Dialect: Debug + Sync {
+    /// Opening and closing delimiters of quoted identifiers.
+    fn identifier_quotes(&self) -> &'static [(char, char)] {
+        &[('"', '"')]
+    }
+
+    /// Quote characters that delimit string literals.
+    fn string_quotes(&self) -> &'static [char] {
+        &['\'']
+    }
+
+    /// Whether a backslash escapes the next character in string literals.
+    fn backslash_escapes(&self) -> bool {
+        false
+    }
+
+    /// Whether `E'...'` strings with backslash escapes exist.
+    fn escape_strings(&self) -> bool {
+        false
+    }
+
+    /// Whether `$$...$$` and `$tag$...$tag$` quote strings.
+    fn dollar_quoted_strings(&self) -> bool {
+        false
+    }
+
+    /// Operators, longest first so that the first match at a position is also
+    /// the longest one (maximal munch).
+    fn operators(&self) -> &'static [&'static str] {
+        STANDARD_OPERATORS
+    }
+
+    /// Words read as keywords, and how reserved each is.
+    fn keywords(&self) -> &'static KeywordSet {
+        &keywords::STANDARD
+    }
+
+    /// Whether `ch` can start an unquoted identifier.
+    fn is_identifier_start(&self, ch: char) -> bool {
+        ch.is_alphabetic() || ch == '_'
+    }
+
+    /// Whether `ch` can continue an unquoted identifier.
+    fn is_identifier_part(&self, ch: char) -> bool {
+        ch.is_alphanumeric() || ch == '_'
+    }
+
+    /// Whether `#` starts a comment running to the end of the line.
+    fn hash_comments(&self) -> bool {
+        false
+    }
+
+    /// Whether a `/*` inside a block comment opens a nested comment that
+    /// needs its own `*/`.
+    fn nested_block_comments(&self) -> bool {
+        false
+    }
+
+    /// Whether `@` starts a reference to a stage of files, e.g.
+    /// `@my_stage/path/`.
+    fn stage_references(&self) -> bool {
+        false
+    }
+
+    /// Placeholder syntaxes that bind parameters are written in.
+    fn parameter_styles(&self) -> &'static [ParameterStyle] {
+        &[ParameterStyle::QuestionMark, ParameterStyle::Colon]
+    }
+
+    /// Whether a `[` right after a name or a closing bracket opens an array
+    /// subscript, e.g. `arr[1]`, even though brackets also quote identifiers.
+    fn bracket_subscripts(&self) -> bool {
+        false
+    }
+
+    /// Word that ends a batch of statements when it stands on a line of its
+    /// own, e.g. `GO`.
+    fn batch_separator(&self) -> Option<&'static str> {
+        None
+    }
+}
+
+/// A way of writing bind parameters, which client libraries and drivers
+/// substitute values for.
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[non_exhaustive]
+pub enum ParameterStyle {
+    /// `?` as used by JDBC and ODBC, or numbered `?1`.
+    QuestionMark,
+    /// Numbered `$1` as used by PostgreSQL and sqlx, or named `$name`.
+    Dollar,
+    /// Named `:name`, or numbered `:1`.
+    Colon,
+    /// Named `@name`, and `@@name` system variables.
+    At,
+    /// `%s` and `%(name)s` as used by Python drivers such as psycopg.
+    Percent,
+}
+
+const ALL_PARAMETER_STYLES: &[ParameterStyle] = &[
+    ParameterStyle::QuestionMark,
+    ParameterStyle::Dollar,
+    ParameterStyle::Colon,
+    ParameterStyle::At,
+    ParameterStyle::Percent,
+];
+
+const STANDARD_OPERATORS: &[&str] = &[
+    "<>", ">=", "<=", "!=", "||", "+", "-", "*", "/", "%", "=", "<", ">",
+];
+
+// Every operator of the built-in dialects
+const GENERIC_OPERATORS: &[&str] = &[
+    "->>", "#>>", "<=>", "!~*", "<>", ">=", "<=", "!=", "==", "||", "&&", "::", ":=", "->", "#>",
+    "@>", "<@", "<<", ">>", "~*", "!~", "~~", "=>", "+", "-", "*", "/", "%", "=", "<", ">", "^",
+    "&", "|", "~", "!", "@", "#", ":",
+];
+
+const POSTGRES_OPERATORS: &[&str] = &[
+    "->>", "#>>", "!~~*", "!~*", "~~*", "!~~", "<>", ">=", "<=", "!=", "||", "&&", "::", ":=",
+    "=>", "->", "#>", "#-", "@>", "<@", "?|", "?&", "<<", ">>", "~*", "!~", "~~", "+", "-", "*",
+    "/", "%", "=", "<", ">", "^", "&", "|", "~", "!", "@", "#", "?", ":",
+];
+
+const MYSQL_OPERATORS: &[&str] = &[
+    "->>", "<=>", "<>", ">=", "<=", "!=", "||", "&&", ":=", "->", "<<", ">>", "+", "-", "*", "/",
+    "%", "=", "<", ">", "^", "&", "|", "~", "!",
+];
+
+const SQL_SERVER_OPERATORS: &[&str] = &[
+    "<>", ">=", "<=", "!=", "!<", "!>", "+=", "-=", "*=", "/=", "%=", "&=", "|=", "^=", "::", "+",
+    "-", "*", "/", "%", "=", "<", ">", "^", "&", "|", "~",
+];
+
+const SQLITE_OPERATORS: &[&str] = &[
+    "->>", "<>", ">=", "<=", "!=", "==", "||", "->", "<<", ">>", "+", "-", "*", "/", "%", "=", "<",
+    ">", "&", "|", "~",
+];
+
+const BIGQUERY_OPERATORS: &[&str] = &[
+    "<>", ">=", "<=", "!=", "||", "<<", ">>", "+", "-", "*", "/", "=", "<", ">", "^", "&", "|", "~",
+];
+
+const SNOWFLAKE_OPERATORS: &[&str] = &[
+    "<>", ">=", "<=", "!=", "||", "::", "=>", "->", "+", "-", "*", "/", "%", "=", "<", ">", ":",
+];
+
+/// Accepts the syntax of all the built-in dialects at once, as far as they do
+/// not conflict.
+#[derive(Debug)]
+pub struct GenericDialect;
+
+impl Dialect for GenericDialect {
+    fn identifier_quotes(&self) -> &'static [(char, char)] {
+        &[('"', '"'), ('`', '`'), ('[', ']')]
+    }
+
+    fn escape_strings(&self) -> bool {
+        true
+    }
+
+    fn dollar_quoted_strings(&self) -> bool {
+        true
+    }
+
+    fn operators(&self) -> &'static [&'static str] {
+        GENERIC_OPERATORS
+    }
+
+    fn parameter_styles(&self) -> &'static [ParameterStyle] {
+        ALL_PARAMETER_STYLES
+    }
+
+    fn bracket_subscripts(&self) -> bool {
+        true
+    }
+}
+
+/// PostgreSQL.
+#[derive(Debug)]
+pub struct PostgresDialect;
+
+impl Dialect for PostgresDialect {
+    fn escape_strings(&self) -> bool {
+        true
+    }
+
+    fn nested_block_comments(&self) -> bool {
+        true
+    }
+
+    fn dollar_quoted_strings(&self) -> bool {
+        true
+    }
+
+    fn operators(&self) -> &'static [&'static str] {
+        POSTGRES_OPERATORS
+    }
+
+    fn keywords(&self) -> &'static KeywordSet {
+        &keywords::POSTGRES
+    }
+
+    // `?` is a JSON operator, so psql's `:name` variables take its place
+    fn parameter_styles(&self) -> &'static [ParameterStyle] {
+        &[
+            ParameterStyle::Dollar,
+            ParameterStyle::Colon,
+            ParameterStyle::Percent,
+        ]
+    }
+}
+
+/// MySQL and MariaDB.
+#[derive(Debug)]
+pub struct MySqlDialect;
+
+impl Dialect for MySqlDialect {
+    fn identifier_quotes(&self) -> &'static [(char, char)] {
+        &[('`', '`')]
+    }
+
+    fn string_quotes(&self) -> &'static [char] {
+        &['\'', '"']
+    }
+
+    fn backslash_escapes(&self) -> bool {
+        true
+    }
+
+    fn operators(&self) -> &'static [&'static str] {
+        MYSQL_OPERATORS
+    }
+
+    fn keywords(&self) -> &'static KeywordSet {
+        &keywords::MYSQL
+    }
+
+    fn hash_comments(&self) -> bool {
+        true
+    }
+
+    fn parameter_styles(&self) -> &'static [ParameterStyle] {
+        &[
+            ParameterStyle::QuestionMark,
+            ParameterStyle::At,
+            ParameterStyle::Percent,
+        ]
+    }
+}
+
+/// Microsoft SQL Server (T-SQL).
+#[derive(Debug)]
+pub struct SqlServerDialect;
+
+impl Dialect for SqlServerDialect {
+    fn identifier_quotes(&self) -> &'static [(char, char)] {
+        &[('"', '"'), ('[', ']')]
+    }
+
+    fn nested_block_comments(&self) -> bool {
+        true
+    }
+
+    fn operators(&self) -> &'static [&'static str] {
+        SQL_SERVER_OPERATORS
+    }
+
+    fn keywords(&self) -> &'static KeywordSet {
+        &keywords::SQL_SERVER
+    }
+
+    // `#temp` and `##global_temp` tables
+    fn is_identifier_start(&self, ch: char) -> bool {
+        ch.is_alphabetic() || matches!(ch, '_' | '#')
+    }
+
+    fn is_identifier_part(&self, ch: char) -> bool {
+        ch.is_alphanumeric() || matches!(ch, '_' | '#' | '$' | '@')
+    }
+
+    fn batch_separator(&self) -> Option<&'static str> {
+        Some("GO")
+    }
+
+    fn parameter_styles(&self) -> &'static [ParameterStyle] {
+        &[ParameterStyle::At, ParameterStyle::QuestionMark]
+    }
+}
+
+/// SQLite.
+#[derive(Debug)]
+pub struct SqliteDialect;
+
+impl Dialect for SqliteDialect {
+    fn identifier_quotes(&self) -> &'static [(char, char)] {
+        &[('"', '"'), ('`', '`'), ('[', ']')]
+    }
+
+    fn operators(&self) -> &'static [&'static str] {
+        SQLITE_OPERATORS
+    }
+
+    fn keywords(&self) -> &'static KeywordSet {
+        &keywords::SQLITE
+    }
+
+    fn parameter_styles(&self) -> &'static [ParameterStyle] {
+        &[
+            ParameterStyle::QuestionMark,
+            ParameterStyle::Colon,
+            ParameterStyle::At,
+            ParameterStyle::Dollar,
+        ]
+    }
+}
+
+/// Google BigQuery.
+#[derive(Debug)]
+pub struct BigQueryDialect;
+
+impl Dialect for BigQueryDialect {
+    fn identifier_quotes(&self) -> &'static [(char, char)] {
+        &[('`', '`')]
+    }
+
+    fn string_quotes(&self) -> &'static [char] {
+        &['\'', '"']
+    }
+
+    fn backslash_escapes(&self) -> bool {
+        true
+    }
+
+    fn operators(&self) -> &'static [&'static str] {
+        BIGQUERY_OPERATORS
+    }
+
+    fn keywords(&self) -> &'static KeywordSet {
+        &keywords::BIGQUERY
+    }
+
+    fn hash_comments(&self) -> bool {
+        true
+    }
+
+    fn parameter_styles(&self) -> &'static [ParameterStyle] {
+        &[ParameterStyle::QuestionMark, ParameterStyle::At]
+    }
+}
+
+/// Snowflake.
+#[derive(Debug)]
+pub struct SnowflakeDialect;
+
+impl Dialect for SnowflakeDialect {
+    fn backslash_escapes(&self) -> bool {
+        true
+    }
+
+    fn dollar_quoted_strings(&self) -> bool {
+        true
+    }
+
+    fn operators(&self) -> &'static [&'static str] {
+        SNOWFLAKE_OPERATORS
+    }
+
+    fn keywords(&self) -> &'static KeywordSet {
+        &keywords::SNOWFLAKE
+    }
+
+    fn stage_references(&self) -> bool {
+        true
+    }
+
+    fn parameter_styles(&self) -> &'static [ParameterStyle] {
+        &[
+            ParameterStyle::QuestionMark,
+            ParameterStyle::Colon,
+            ParameterStyle::Dollar,
+        ]
+    }
+}
+
+/// The built-in dialects, as named in options.
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize),
+    serde(rename_all = "lowercase")
+)]
+#[non_exhaustive]
+pub enum SqlDialect {
+    /// Accepts the syntax of all the built-in dialects.
+    Generic,
+    /// PostgreSQL.
+    Postgres,
+    /// MySQL and MariaDB.
+    Mysql,
+    /// Microsoft SQL Server (T-SQL).
+    #[cfg_attr(feature = "cli", value(name = "sqlserver"))]
+    SqlServer,
+    /// SQLite.
+    Sqlite,
+    /// Google BigQuery.
+    #[cfg_attr(feature = "cli", value(name = "bigquery"))]
+    BigQuery,
+    /// Snowflake.
+    Snowflake,
+}
+
+impl SqlDialect {
+    /// The lexical rules of this dialect.
+    pub fn dialect(self) -> &'static dyn Dialect {
+        match self {
+            SqlDialect::Generic => &GenericDialect,
+            SqlDialect::Postgres => &PostgresDialect,
+            SqlDialect::Mysql => &MySqlDialect,
+            SqlDialect::SqlServer => &SqlServerDialect,
+            SqlDialect::Sqlite => &SqliteDialect,
+            SqlDialect::BigQuery => &BigQueryDialect,
+            SqlDialect::Snowflake => &SnowflakeDialect,
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use similar::TextDiff;
+
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const CYAN: &str = "\x1b[36m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+// Renders a unified diff turning `original` into `formatted`, both labelled
+// `name`, with ANSI colors when `color` is set
+pub fn unified_diff(name: &str, original: &str, formatted: &str, color: bool) -> String {
+    let diff: String = TextDiff::from_lines(original, formatted)
+        .unified_diff()
+        .context_radius(3)
+        .header(
+            &format!("{} (original)", name),
+            &format!("{} (formatted)", name),
+        )
+        .missing_newline_hint(true)
+        .to_string();
+
+    if !color {
+        return diff;
+    }
+
+    let mut colored: String = String::new();
+    // The `---` and `+++` lines before the first hunk name the files; later
+    // ones are removed or added lines such as `-- comment`
+    let mut in_hunks: bool = false;
+    for line in diff.lines() {
+        let style: &str = if line.starts_with("@@") {
+            in_hunks = true;
+            CYAN
+        } else if !in_hunks && (line.starts_with("---") || line.starts_with("+++")) {
+            BOLD
+        } else if line.starts_with('-') {
+            RED
+        } else if line.starts_with('+') {
+            GREEN
+        } else {
+            ""
+        };
+
+        if style.is_empty() {
+            colored.push_str(line);
+        } else {
+            colored.push_str(&format!("{}{}{}", style, line, RESET));
+        }
+        colored.push('\n');
+    }
+    colored
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn styles_only_the_file_headers_bold() {
+        let diff: String = unified_diff("q.sql", "-- x\nselect 1\n", "select 1\n", true);
+        let lines: Vec<&str> = diff.lines().collect();
+        assert_eq!(lines[0], format!("{BOLD}--- q.sql (original){RESET}"));
+        assert_eq!(lines[1], format!("{BOLD}+++ q.sql (formatted){RESET}"));
+        assert!(lines[2].starts_with(CYAN));
+        assert_eq!(lines[3], format!("{RED}--- x{RESET}"));
+    }
+}
@@ -0,0 +1,339 @@
+// Document algebra the formatter lays SQL out in, after Wadler's "prettier
+// printer": groups are printed flat on one line when they fit within the line
+// width, and otherwise have their line breaks taken, outermost group first.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Doc {
+    Text(String),
+    Concat(Vec<Doc>),
+    // Printed flat if everything up to the next possible line break fits
+    Group(Box<Doc>),
+    // Line breaks inside are indented one level deeper
+    Indent(Box<Doc>),
+    // A space, or a line break when the enclosing group is broken
+    Line,
+    // Nothing, or a line break when the enclosing group is broken
+    SoftLine,
+    // Always a line break. Line breaks never stack up: breaking a line that
+    // has nothing on it yet does nothing.
+    HardLine,
+    // Text put in front of the item it separates from the one before, e.g. a
+    // leading comma. Comments trailing the line before stay on that line.
+    Separator(String),
+    // Ends the current line and leaves this many empty lines after it
+    BlankLines(usize),
+    // A comment on a line of its own
+    LeadingComment(String),
+    // A comment kept at the end of the line holding the code before it, even
+    // when a line break has already been printed after that code. Line
+    // comments run to the end of the line, so nothing can follow them on it.
+    TrailingComment { text: String, line: bool },
+}
+
+impl Doc {
+    pub fn nil() -> Doc {
+        Doc::Concat(Vec::new())
+    }
+
+    pub fn text(text: impl Into<String>) -> Doc {
+        Doc::Text(text.into())
+    }
+
+    pub fn group(doc: Doc) -> Doc {
+        Doc::Group(Box::new(doc))
+    }
+
+    pub fn indent(doc: Doc) -> Doc {
+        Doc::Indent(Box::new(doc))
+    }
+
+    // Puts `separator` between each of `docs`
+    pub fn join(docs: Vec<Doc>, separator: Doc) -> Doc {
+        let mut parts: Vec<Doc> = Vec::with_capacity(docs.len() * 2);
+        for (i, doc) in docs.into_iter().enumerate() {
+            if i > 0 {
+                parts.push(separator.clone());
+            }
+            parts.push(doc);
+        }
+        Doc::Concat(parts)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Mode {
+    Flat,
+    Break,
+}
+
+// Prints documents to text
+pub struct Printer {
+    max_width: usize,
+    // Text written for one indent level, and the width it takes up
+    indent_unit: String,
+    indent_width: usize,
+    output: String,
+    // Indent level of the current line, written once text is put on it
+    indent: usize,
+    // Trailing line comments to write once the current line is finished
+    line_suffix: Vec<String>,
+    // Whether the last finished line ends in a comment, which nothing more
+    // can be put after
+    line_commented: bool,
+    // Whether the current line holds nothing but a separator so far
+    separator_only: bool,
+}
+
+impl Printer {
+    pub fn new(max_width: usize, indent_unit: String, indent_width: usize) -> Self {
+        Printer {
+            max_width,
+            indent_unit,
+            indent_width,
+            output: String::new(),
+            indent: 0,
+            line_suffix: Vec::new(),
+            line_commented: false,
+            separator_only: false,
+        }
+    }
+
+    pub fn print(mut self, doc: &Doc) -> String {
+        let mut stack: Vec<(usize, Mode, &Doc)> = vec![(0, Mode::Break, doc)];
+
+        while let Some((indent, mode, doc)) = stack.pop() {
+            match doc {
+                Doc::Text(text) => self.write(indent, text),
+                Doc::Separator(text) => {
+                    let at_line_start: bool = self.at_line_start();
+                    self.write(indent, text);
+                    self.separator_only = at_line_start;
+                }
+                Doc::Concat(parts) => {
+                    stack.extend(parts.iter().rev().map(|part| (indent, mode, part)));
+                }
+                Doc::Group(content) => {
+                    let mode: Mode = if mode == Mode::Flat || self.fits(indent, content, &stack) {
+                        Mode::Flat
+                    } else {
+                        Mode::Break
+                    };
+                    stack.push((indent, mode, content));
+                }
+                Doc::Indent(content) => stack.push((indent + 1, mode, content)),
+                Doc::Line if mode == Mode::Flat => self.write(indent, " "),
+                Doc::SoftLine if mode == Mode::Flat => {}
+                Doc::Line | Doc::SoftLine | Doc::HardLine => self.new_line(indent),
+                Doc::BlankLines(count) => {
+                    self.new_line(indent);
+                    for _ in 0..*count {
+                        self.output.push('\n');
+                    }
+                    if *count > 0 {
+                        self.line_commented = false;
+                    }
+                }
+                Doc::LeadingComment(text) => {
+                    self.new_line(indent);
+                    self.write(indent, text);
+                    self.new_line(indent);
+                    self.line_commented = true;
+                }
+                Doc::TrailingComment { text, line } => self.trailing_comment(indent, text, *line),
+            }
+        }
+
+        self.new_line(0);
+        self.output
+    }
+
+    fn at_line_start(&self) -> bool {
+        self.output.is_empty() || self.output.ends_with('\n')
+    }
+
+    // Current column, counting the pending indent of an empty line
+    fn column(&self) -> usize {
+        if self.at_line_start() {
+            return self.indent * self.indent_width;
+        }
+
+        let line: &str = self
+            .output
+            .rsplit_once('\n')
+            .map_or(self.output.as_str(), |(_, line)| line);
+        line.chars()
+            .map(|ch| if ch == '\t' { self.indent_width } else { 1 })
+            .sum()
+    }
+
+    // Writes text, indenting first if it starts a line. Leading spaces are
+    // dropped at the start of a line. Code after a line comment waiting in
+    // the line suffix goes on the next line, so that the comment stays with
+    // the code it followed; only punctuation closing that code comes first.
+    fn write(&mut self, indent: usize, text: &str) {
+        let is_code: bool =
+            !text.trim_start_matches(' ').is_empty() && !text.starts_with([',', ';', ')', ']']);
+        if is_code && !self.line_suffix.is_empty() {
+            self.new_line(indent);
+        }
+
+        self.separator_only = false;
+        if self.at_line_start() {
+            let text: &str = text.trim_start_matches(' ');
+            if text.is_empty() {
+                return;
+            }
+            self.indent = indent;
+            self.output.push_str(&self.indent_unit.repeat(indent));
+            self.output.push_str(text);
+        } else {
+            self.output.push_str(text);
+        }
+    }
+
+    fn new_line(&mut self, indent: usize) {
+        let commented: bool = !self.line_suffix.is_empty();
+        let content_len: usize = self.output.trim_end_matches(' ').len();
+        self.output.truncate(content_len);
+        for comment in std::mem::take(&mut self.line_suffix) {
+            self.output.push(' ');
+            self.output.push_str(&comment);
+        }
+
+        let content_len: usize = self.output.trim_end_matches(' ').len();
+        self.output.truncate(content_len);
+        if !self.at_line_start() {
+            self.output.push('\n');
+            self.line_commented = commented;
+        }
+        self.indent = indent;
+    }
+
+    // A line comment always ends the line it is written on, so it waits in
+    // the line suffix until the line is finished. Each line holds at most one
+    // line comment; another one gets a line of its own.
+    fn trailing_comment(&mut self, indent: usize, text: &str, line: bool) {
+        if self.line_commented && self.at_line_start() {
+            self.write(indent, text);
+            if line {
+                self.new_line(indent);
+            }
+        } else if line && !self.line_suffix.is_empty() {
+            self.new_line(indent);
+            self.write(indent, text);
+            self.new_line(indent);
+        } else if self.at_line_start() && !self.output.is_empty() {
+            // Pull the comment back up onto the line of the code it trails
+            self.output.pop();
+            self.line_suffix.push(text.to_string());
+            self.new_line(indent);
+        } else if self.separator_only
+            && let Some(line_end) = self.output.rfind('\n')
+        {
+            // Likewise past a separator that starts the line
+            self.output.insert_str(line_end, &format!(" {}", text));
+        } else if line {
+            self.line_suffix.push(text.to_string());
+        } else {
+            // Keep a single space on either side, moving the space already
+            // written after the code to after the comment
+            let spaced: bool = self.output.ends_with(' ');
+            if !spaced {
+                self.write(indent, " ");
+            }
+            self.write(indent, text);
+            if spaced {
+                self.write(indent, " ");
+            }
+        }
+    }
+
+    // Checks whether `doc` printed flat, followed by the rest of the stack up
+    // to its next line break, fits in what is left of the current line
+    fn fits(&self, indent: usize, doc: &Doc, rest: &[(usize, Mode, &Doc)]) -> bool {
+        let mut remaining: isize = self.max_width as isize - self.column() as isize;
+        let mut at_line_start: bool = self.at_line_start();
+        // Set by a line comment, after which nothing more fits on the line
+        let mut line_ended: bool = false;
+        // Set by a line comment ahead of all the text of `doc`, which then
+        // starts on the next line
+        let mut line_started: bool = false;
+        let mut measured: bool = false;
+        let mut stack: Vec<(usize, Mode, &Doc)> = vec![(indent, Mode::Flat, doc)];
+        let mut rest: std::slice::Iter<(usize, Mode, &Doc)> = rest.iter();
+        // Set once `doc` itself has been measured and what follows it is
+        let mut in_rest: bool = false;
+
+        while remaining >= 0 {
+            if stack.is_empty() {
+                // A line comment that `doc` ends with only ends the line
+                if line_ended {
+                    return true;
+                }
+                in_rest = true;
+            }
+            let Some((indent, mode, doc)) = stack.pop().or_else(|| rest.next_back().copied())
+            else {
+                return true;
+            };
+
+            match doc {
+                Doc::Text(text) | Doc::Separator(text) => {
+                    let text: &str = if at_line_start {
+                        text.trim_start_matches(' ')
+                    } else {
+                        text
+                    };
+                    if !text.is_empty() {
+                        if line_ended {
+                            return false;
+                        }
+                        if line_started {
+                            line_started = false;
+                            remaining =
+                                self.max_width as isize - (indent * self.indent_width) as isize;
+                        }
+                        at_line_start = false;
+                        measured = true;
+                    }
+                    // Text spanning lines, e.g. a dollar-quoted body, ends the
+                    // line being measured at its first line break
+                    if let Some((first_line, _)) = text.split_once('\n') {
+                        return remaining >= first_line.chars().count() as isize;
+                    }
+                    remaining -= text.chars().count() as isize;
+                }
+                Doc::Concat(parts) => {
+                    stack.extend(parts.iter().rev().map(|part| (indent, mode, part)));
+                }
+                Doc::Group(content) => stack.push((indent, mode, content)),
+                Doc::Indent(content) => stack.push((indent + 1, mode, content)),
+                Doc::Line if mode == Mode::Flat => remaining -= 1,
+                Doc::SoftLine if mode == Mode::Flat => {}
+                // A line break that will be taken ends the line being measured,
+                // unless nothing has been put on it yet
+                Doc::Line | Doc::SoftLine | Doc::HardLine | Doc::BlankLines(_)
+                    if mode == Mode::Break && !at_line_start =>
+                {
+                    return true;
+                }
+                Doc::Line | Doc::SoftLine | Doc::HardLine | Doc::BlankLines(_) => {
+                    if !at_line_start {
+                        return false;
+                    }
+                }
+                // A comment on a line of its own cannot share the line with
+                // code, but after the group it just ends the line
+                Doc::LeadingComment(_) => return in_rest,
+                // Pulled back up onto the line before
+                Doc::TrailingComment { .. } if at_line_start => {}
+                Doc::TrailingComment { .. } if line_ended => return false,
+                Doc::TrailingComment { line: true, .. } if !measured => line_started = true,
+                // Likewise a line comment after the group ends the line
+                Doc::TrailingComment { line: true, .. } if in_rest => return true,
+                Doc::TrailingComment { line: true, .. } => line_ended = true,
+                Doc::TrailingComment { text, .. } => remaining -= text.chars().count() as isize + 1,
+            }
+        }
+        false
+    }
+}
@@ -0,0 +1,102 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::lexer::{LexError, Span};
+
+/// Error raised when SQL cannot be formatted.
+#[derive(Debug, PartialEq, Clone)]
+#[non_exhaustive]
+pub enum FormatError {
+    /// The source could not be split into tokens.
+    Lex(LexError),
+}
+
+impl FormatError {
+    /// The source range the error points at.
+    pub fn span(&self) -> Span {
+        match self {
+            FormatError::Lex(err) => err.span,
+        }
+    }
+
+    fn message(&self) -> &str {
+        match self {
+            FormatError::Lex(err) => &err.message,
+        }
+    }
+
+    /// Renders the error for people, quoting the source line it occurred on
+    /// with a caret under the offending text:
+    ///
+    /// ```text
+    /// unterminated string literal
+    ///  --> query.sql:3:15
+    ///   |
+    /// 3 | WHERE name = 'abc
+    ///   |              ^^^^
+    /// ```
+    ///
+    /// `name` names the source in the location line, and `source` is the text
+    /// the error was raised for.
+    pub fn render(&self, name: &str, source: &str) -> String {
+        let span: Span = self.span();
+        let line_text: &str = source
+            .lines()
+            .nth(span.start.line - 1)
+            .unwrap_or_default()
+            .trim_end();
+        let line_number: String = span.start.line.to_string();
+        let gutter: String = " ".repeat(line_number.len());
+
+        // Tabs are kept so that the caret lines up under them
+        let padding: String = line_text
+            .chars()
+            .take(span.start.column - 1)
+            .map(|ch| if ch == '\t' { '\t' } else { ' ' })
+            .collect();
+        let line_len: usize = line_text.chars().count();
+        let end_column: usize = if span.end.line == span.start.line {
+            span.end.column
+        } else {
+            line_len + 1
+        };
+        let carets: String = "^".repeat(end_column.saturating_sub(span.start.column).max(1));
+
+        format!(
+            "{}\n{}--> {}:{}:{}\n{} |\n{} | {}\n{} | {}{}",
+            self.message(),
+            gutter,
+            name,
+            span.start.line,
+            span.start.column,
+            gutter,
+            line_number,
+            line_text,
+            gutter,
+            padding,
+            carets
+        )
+    }
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FormatError::Lex(err) => err.fmt(f),
+        }
+    }
+}
+
+impl Error for FormatError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            FormatError::Lex(err) => Some(err),
+        }
+    }
+}
+
+impl From<LexError> for FormatError {
+    fn from(err: LexError) -> Self {
+        FormatError::Lex(err)
+    }
+}
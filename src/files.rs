@@ -0,0 +1,147 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+
+// Decides which files found while walking a directory get formatted. Patterns
+// are matched against the path relative to the directory being walked.
+pub struct FileFilter {
+    include: GlobSet,
+    exclude: GlobSet,
+}
+
+impl FileFilter {
+    pub fn new(include: &[String], exclude: &[String]) -> Result<Self, globset::Error> {
+        Ok(FileFilter {
+            include: Self::build(include)?,
+            exclude: Self::build(exclude)?,
+        })
+    }
+
+    fn build(patterns: &[String]) -> Result<GlobSet, globset::Error> {
+        let mut builder: GlobSetBuilder = GlobSetBuilder::new();
+        for pattern in patterns {
+            builder.add(Glob::new(pattern)?);
+        }
+        builder.build()
+    }
+
+    // Collects the files to format under `root`, in a stable order. Excluded
+    // directories are not descended into.
+    pub fn collect(&self, root: &Path) -> io::Result<Vec<PathBuf>> {
+        let mut files: Vec<PathBuf> = Vec::new();
+        self.walk(root, root, &mut files)?;
+        Ok(files)
+    }
+
+    fn walk(&self, root: &Path, dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+        let mut entries: Vec<PathBuf> = fs::read_dir(dir)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<io::Result<Vec<PathBuf>>>()?;
+        entries.sort();
+
+        for path in entries {
+            let relative: &Path = path.strip_prefix(root).unwrap_or(&path);
+            if self.exclude.is_match(relative) {
+                continue;
+            }
+
+            if path.is_dir() {
+                self.walk(root, &path, files)?;
+            } else if self.include.is_match(relative) {
+                files.push(path);
+            }
+        }
+        Ok(())
+    }
+}
+
+// Replaces the contents of `path` without ever leaving it half written: the
+// new contents go to a temporary file next to it, which is then renamed over it
+pub fn write_atomically(path: &Path, contents: &str) -> io::Result<()> {
+    let dir: &Path = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let file_name: String = path
+        .file_name()
+        .map_or_else(String::new, |name| name.to_string_lossy().into_owned());
+    let temp_path: PathBuf = dir.join(format!(".{}.{}.tmp", file_name, process::id()));
+
+    let result: io::Result<()> = (|| {
+        let mut file: fs::File = fs::File::create(&temp_path)?;
+        file.write_all(contents.as_bytes())?;
+        file.sync_all()?;
+        fs::set_permissions(&temp_path, fs::metadata(path)?.permissions())?;
+        fs::rename(&temp_path, path)
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // An empty directory of its own for the test called `name`
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir: PathBuf =
+            std::env::temp_dir().join(format!("sql-formatter-files-{}-{}", name, process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn collects_included_files_outside_excluded_directories() {
+        let dir: PathBuf = scratch_dir("collect");
+        fs::create_dir_all(dir.join("migrations/old")).unwrap();
+        for file in [
+            "b.sql",
+            "a.sql",
+            "notes.txt",
+            "migrations/c.sql",
+            "migrations/old/d.sql",
+        ] {
+            fs::write(dir.join(file), "").unwrap();
+        }
+
+        let filter: FileFilter = FileFilter::new(
+            &[String::from("**/*.sql")],
+            &[String::from("migrations/old")],
+        )
+        .unwrap();
+        let files: Vec<PathBuf> = filter.collect(&dir).unwrap();
+        assert_eq!(
+            files,
+            [
+                dir.join("a.sql"),
+                dir.join("b.sql"),
+                dir.join("migrations/c.sql")
+            ]
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn replaces_the_contents_without_leaving_a_temporary_file() {
+        let dir: PathBuf = scratch_dir("write");
+        let path: PathBuf = dir.join("q.sql");
+        fs::write(&path, "select 1").unwrap();
+
+        write_atomically(&path, "SELECT 1\n").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "SELECT 1\n");
+        let entries: usize = fs::read_dir(&dir).unwrap().count();
+        assert_eq!(entries, 1);
+
+        // Nothing is created in place of a file that does not exist
+        assert!(write_atomically(&dir.join("missing.sql"), "SELECT 1\n").is_err());
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,1828 @@
+use crate::ast::*;
+use crate::dialect::Dialect;
+use crate::doc::{Doc, Printer};
+use crate::keywords::{self, KeywordSet};
+use crate::lexer::{Comment, CommentKind, DollarQuoted, Span, SpannedToken, Token, tokenize};
+use crate::options::{CommaPosition, FormatOptions};
+use crate::parser::Parser;
+use crate::splitter::{SplitStatement, StatementSplitter};
+
+// Functions whose names follow the function case option rather than the
+// identifier case option
+const BUILTIN_FUNCTIONS: &[&str] = &[
+    "ABS",
+    "ARRAY_AGG",
+    "AVG",
+    "CEIL",
+    "CEILING",
+    "CHAR_LENGTH",
+    "COALESCE",
+    "CONCAT",
+    "CONCAT_WS",
+    "COUNT",
+    "CUME_DIST",
+    "DATE_PART",
+    "DATE_TRUNC",
+    "DENSE_RANK",
+    "EXTRACT",
+    "FIRST_VALUE",
+    "FLOOR",
+    "GREATEST",
+    "IFNULL",
+    "LAG",
+    "LAST_VALUE",
+    "LEAD",
+    "LEAST",
+    "LEFT",
+    "LENGTH",
+    "LOWER",
+    "LTRIM",
+    "MAX",
+    "MIN",
+    "MOD",
+    "NOW",
+    "NTILE",
+    "NTH_VALUE",
+    "NULLIF",
+    "PERCENT_RANK",
+    "POSITION",
+    "POWER",
+    "RANK",
+    "REPLACE",
+    "RIGHT",
+    "ROUND",
+    "ROW_NUMBER",
+    "RTRIM",
+    "STRING_AGG",
+    "SUBSTR",
+    "SUBSTRING",
+    "SUM",
+    "TO_CHAR",
+    "TO_DATE",
+    "TRIM",
+    "UPPER",
+];
+
+// Keywords that start a clause in the raw layout, with the clause body on
+// the same line if it fits, or else indented on the lines below them
+const RAW_CLAUSE_KEYWORDS: &[&str] = &[
+    "SELECT",
+    "FROM",
+    "WHERE",
+    "HAVING",
+    "UPDATE",
+    "SET",
+    "GROUP BY",
+    "ORDER BY",
+    "FOR UPDATE",
+    "FOR SHARE",
+];
+
+// Keywords that start a join in the raw layout, on a line of its own inside
+// the clause around it
+const RAW_JOIN_KEYWORDS: &[&str] = &[
+    "LEFT",
+    "RIGHT",
+    "INNER",
+    "JOIN",
+    "INNER JOIN",
+    "LEFT JOIN",
+    "LEFT OUTER JOIN",
+    "RIGHT JOIN",
+    "RIGHT OUTER JOIN",
+    "FULL JOIN",
+    "FULL OUTER JOIN",
+    "CROSS JOIN",
+    "NATURAL JOIN",
+    "CROSS APPLY",
+    "OUTER APPLY",
+];
+
+// Keywords other than clause keywords that a name follows in the raw layout
+const RAW_NAME_KEYWORDS: &[&str] = &[
+    "AND", "AS", "BY", "ELSE", "INTO", "ON", "OR", "TABLE", "THEN", "VIEW", "WHEN",
+];
+
+// Keyword phrases whose last word stays a keyword even where a name could go
+const RAW_KEYWORD_PHRASES: &[&[&str]] = &[&["AS", "IDENTITY"], &["WHEN", "MATCHED"]];
+
+// Keywords that combine queries in the raw layout, on a line of their own
+const RAW_SET_OPERATORS: &[&str] = &[
+    "UNION",
+    "UNION ALL",
+    "INTERSECT",
+    "INTERSECT ALL",
+    "EXCEPT",
+    "EXCEPT ALL",
+];
+
+// A comment waiting to be written back into the output
+#[derive(Debug, Clone)]
+struct PendingComment {
+    comment: Comment,
+    span: Span,
+    // True when the comment follows code on the same source line
+    trailing: bool,
+    // Where the code token before the comment ends
+    follows: Option<usize>,
+}
+
+// What opened an indent level of the raw layout
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Scope {
+    Statement,
+    Parenthesis,
+    Bracket,
+    // A clause keyword, followed by the clause body on its line if it fits
+    Clause,
+    // A join, or a `WHEN` branch of MERGE, on a line of its own that goes on
+    // below it when it does not fit
+    Join,
+    Case,
+}
+
+// Kinds of raw statement whose keywords outside parentheses are laid out
+// differently from those of a query
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum RawStatement {
+    // GRANT and REVOKE, where `SELECT` or `UPDATE` names a privilege
+    Privileges,
+    // MERGE, with a line for each `WHEN` branch
+    Merge,
+    // ALTER, where `SET` changes a property
+    Alter,
+    Other,
+}
+
+impl RawStatement {
+    fn of(tokens: &[SpannedToken]) -> Self {
+        match tokens.first().map(|first| &first.token) {
+            Some(Token::Keyword(word)) => match word.to_uppercase().as_str() {
+                "GRANT" | "REVOKE" => RawStatement::Privileges,
+                "MERGE" => RawStatement::Merge,
+                "ALTER" => RawStatement::Alter,
+                _ => RawStatement::Other,
+            },
+            _ => RawStatement::Other,
+        }
+    }
+}
+
+// An open indent level of the raw layout
+struct Level {
+    scope: Scope,
+    // What opened the level, e.g. a clause keyword or a `(`
+    head: Doc,
+    // The documents laid out in the level so far
+    docs: Vec<Doc>,
+}
+
+// The open indent levels of the raw layout, innermost last
+struct IndentLevels(Vec<Level>);
+
+impl IndentLevels {
+    fn new() -> Self {
+        IndentLevels(vec![Level {
+            scope: Scope::Statement,
+            head: Doc::nil(),
+            docs: Vec::new(),
+        }])
+    }
+
+    // Documents of the innermost level
+    fn current(&mut self) -> &mut Vec<Doc> {
+        &mut self.0.last_mut().expect("statement level").docs
+    }
+
+    fn open(&mut self, scope: Scope, head: Doc, docs: Vec<Doc>) {
+        self.0.push(Level { scope, head, docs });
+    }
+
+    // Closes the innermost level, indenting it within the one around it. A
+    // clause or a join stays on one line if it fits.
+    fn close(&mut self) {
+        let level: Level = self.0.pop().expect("inner level");
+        // A clause with nothing in it, e.g. `FOR UPDATE`, ends at its keyword
+        let body: Doc = if level
+            .docs
+            .iter()
+            .all(|doc| *doc == Doc::Line || *doc == Doc::nil())
+        {
+            Doc::nil()
+        } else {
+            Doc::indent(Doc::Concat(level.docs))
+        };
+        let doc: Doc = Doc::Concat(vec![level.head, body]);
+        let doc: Doc = match level.scope {
+            Scope::Clause | Scope::Join => Doc::group(doc),
+            _ => doc,
+        };
+        self.current().push(doc);
+    }
+
+    // Closes the clauses and joins open in the innermost parenthesis, if any
+    fn close_clauses(&mut self) {
+        while matches!(self.scope(), Scope::Clause | Scope::Join) {
+            self.close();
+        }
+    }
+
+    // Closes the join open in the innermost clause, if any
+    fn close_join(&mut self) {
+        if self.scope() == Scope::Join {
+            self.close();
+        }
+    }
+
+    // What opened the innermost level
+    fn scope(&self) -> Scope {
+        self.0.last().expect("statement level").scope
+    }
+
+    fn is_open(&self, scope: Scope) -> bool {
+        self.0.iter().any(|level| level.scope == scope)
+    }
+
+    // Closes the innermost level opened by `scope` together with the levels
+    // opened inside it, followed by `close`. Its contents stay on the line if
+    // they fit. Returns false if no such level is open.
+    fn close_delimited(&mut self, scope: Scope, close: Doc) -> bool {
+        if !self.is_open(scope) {
+            return false;
+        }
+        while self.scope() != scope {
+            self.close();
+        }
+        let level: Level = self.0.pop().expect("delimited level");
+        self.current().push(Doc::group(Doc::Concat(vec![
+            level.head,
+            Doc::indent(Doc::Concat(level.docs)),
+            close,
+        ])));
+        true
+    }
+
+    fn finish(mut self) -> Doc {
+        while self.0.len() > 1 {
+            self.close();
+        }
+        Doc::Concat(self.0.pop().expect("statement level").docs)
+    }
+}
+
+pub struct Formatter {
+    tokens: Vec<SpannedToken>,
+    options: FormatOptions,
+    // Comments in source order; those before `next_comment` have been placed
+    comments: Vec<PendingComment>,
+    next_comment: usize,
+}
+
+impl Formatter {
+    // Creates new formatter instance
+    pub fn new(tokens: Vec<SpannedToken>, options: FormatOptions) -> Self {
+        Formatter {
+            tokens,
+            options,
+            comments: Vec::new(),
+            next_comment: 0,
+        }
+    }
+
+    // Function to format SQL: parses each statement, lays it out from its
+    // syntax tree as a document (falling back to the raw tokens for statements
+    // the parser does not understand) and prints that within the line width
+    pub fn format(&mut self) -> String {
+        let doc: Doc = self.document();
+        let printer: Printer = Printer::new(
+            self.options.max_line_width,
+            self.options.indent_unit(),
+            self.options.indent_width,
+        );
+        printer.print(&doc).trim().to_string()
+    }
+
+    // Lays out every statement, with the comments around them
+    fn document(&mut self) -> Doc {
+        let mut significant: Vec<SpannedToken> = Vec::new();
+        // Source line on which the last non-whitespace token ended
+        let mut last_line: Option<usize> = None;
+        let mut last_end: Option<usize> = None;
+
+        for SpannedToken { token, span } in &self.tokens {
+            match token {
+                Token::Whitespace => {}
+                Token::Comment(comment) => self.comments.push(PendingComment {
+                    comment: comment.clone(),
+                    span: *span,
+                    trailing: last_line == Some(span.start.line),
+                    follows: last_end,
+                }),
+                _ => {
+                    significant.push(SpannedToken {
+                        token: token.clone(),
+                        span: *span,
+                    });
+                    last_line = Some(span.end.line);
+                    last_end = Some(span.end.offset);
+                }
+            }
+        }
+
+        let dialect: &'static dyn Dialect = self.options.dialect.dialect();
+        let statements: Vec<SplitStatement> =
+            StatementSplitter::new(&significant, dialect.batch_separator()).collect();
+        let mut docs: Vec<Doc> = Vec::new();
+        let mut written_any: bool = false;
+
+        for (i, statement) in statements.iter().enumerate() {
+            let body: &[SpannedToken] = statement.tokens;
+
+            if !body.is_empty() {
+                // A statement holding source that could not be tokenized is
+                // laid out token by token, with that source kept verbatim
+                let invalid: bool = body
+                    .iter()
+                    .any(|spanned| matches!(spanned.token, Token::Invalid(_)));
+                let parsed: Statement = if invalid {
+                    Statement::Raw(body.to_vec())
+                } else {
+                    Parser::new(body, dialect)
+                        .parse_statement()
+                        .unwrap_or_else(|_| Statement::Raw(body.to_vec()))
+                };
+                if written_any {
+                    docs.push(Doc::BlankLines(self.options.lines_between_statements));
+                }
+                docs.push(self.format_statement(&parsed));
+                written_any = true;
+            }
+            if statement.terminated {
+                docs.push(Doc::text(";"));
+            }
+            if let Some(separator) = statement.batch_separator {
+                let text: String = match &separator.token {
+                    Token::Keyword(word) | Token::Identifier(word) => {
+                        self.options.keyword_case.apply(word)
+                    }
+                    token => self.token_text(token),
+                };
+                docs.push(Doc::HardLine);
+                docs.push(self.spanned(text, separator.span));
+                written_any = true;
+            }
+
+            let next_start: usize = statements
+                .iter()
+                .skip(i + 1)
+                .find_map(|next| next.tokens.first())
+                .map_or(usize::MAX, |next| next.span.start.offset);
+            docs.push(self.trailing_comments(next_start));
+        }
+        docs.push(self.comments_before(usize::MAX));
+        Doc::Concat(docs)
+    }
+
+    // ---- Comments ----
+
+    fn comment(pending: &PendingComment) -> Doc {
+        if pending.trailing {
+            Doc::TrailingComment {
+                text: pending.comment.text.clone(),
+                line: pending.comment.kind == CommentKind::Line,
+            }
+        } else {
+            Doc::LeadingComment(pending.comment.text.clone())
+        }
+    }
+
+    // Places every pending comment that starts before `offset`
+    fn comments_before(&mut self, offset: usize) -> Doc {
+        let mut docs: Vec<Doc> = Vec::new();
+        while let Some(pending) = self.comments.get(self.next_comment)
+            && pending.span.start.offset < offset
+        {
+            docs.push(Self::comment(pending));
+            self.next_comment += 1;
+        }
+        Doc::Concat(docs)
+    }
+
+    // Places the pending comments before `offset` that trail the code just laid out
+    fn trailing_comments(&mut self, offset: usize) -> Doc {
+        let mut docs: Vec<Doc> = Vec::new();
+        while let Some(pending) = self.comments.get(self.next_comment)
+            && pending.trailing
+            && pending.span.start.offset < offset
+        {
+            docs.push(Self::comment(pending));
+            self.next_comment += 1;
+        }
+        Doc::Concat(docs)
+    }
+
+    // Places the pending comments that trail the code token ending at `offset`
+    fn comments_after(&mut self, offset: usize) -> Doc {
+        let mut docs: Vec<Doc> = Vec::new();
+        while let Some(pending) = self.comments.get(self.next_comment)
+            && pending.trailing
+            && pending.follows == Some(offset)
+        {
+            docs.push(Self::comment(pending));
+            self.next_comment += 1;
+        }
+        Doc::Concat(docs)
+    }
+
+    // ---- Leaves ----
+
+    // Source text found at `span`, preceded by any comments before it and
+    // followed by those trailing it
+    fn spanned(&mut self, text: String, span: Span) -> Doc {
+        Doc::Concat(vec![
+            self.comments_before(span.start.offset),
+            Doc::Text(text),
+            self.comments_after(span.end.offset),
+        ])
+    }
+
+    fn keyword(&mut self, keyword: &Keyword) -> Doc {
+        let text: String = self.options.keyword_case.apply(&keyword.text);
+        self.spanned(text, keyword.span)
+    }
+
+    // Keywords the layout adds itself, e.g. ` ON ` in a join
+    fn keyword_text(&self, text: &str) -> Doc {
+        Doc::Text(self.options.keyword_case.apply(text))
+    }
+
+    // An identifier in the identifier case unless it is quoted
+    fn ident(&mut self, ident: &Ident) -> Doc {
+        let text: String = if ident.quoted {
+            ident.value.clone()
+        } else {
+            self.identifier_text(&ident.value)
+        };
+        self.spanned(text, ident.span)
+    }
+
+    // An unquoted identifier in the identifier case
+    fn identifier_text(&self, value: &str) -> String {
+        self.options.identifier_case.apply(value)
+    }
+
+    // An operator, in the keyword case when it is made of keywords
+    fn operator(&mut self, op: &Operator) -> Doc {
+        let text: String = if op.text.starts_with(char::is_alphabetic) {
+            self.options.keyword_case.apply(&op.text)
+        } else {
+            op.text.clone()
+        };
+        self.spanned(text, op.span)
+    }
+
+    fn object_name(&mut self, name: &ObjectName) -> Doc {
+        let parts: Vec<Doc> = name.0.iter().map(|part| self.ident(part)).collect();
+        Doc::join(parts, Doc::text("."))
+    }
+
+    fn ident_list(&mut self, idents: &[Ident]) -> Doc {
+        self.parenthesized(idents, Self::ident)
+    }
+
+    fn alias(&mut self, alias: &Option<Alias>) -> Doc {
+        let Some(alias) = alias else {
+            return Doc::nil();
+        };
+
+        let mut docs: Vec<Doc> = vec![Doc::text(" ")];
+        if let Some(keyword) = &alias.keyword {
+            docs.push(self.keyword(keyword));
+            docs.push(Doc::text(" "));
+        }
+        docs.push(self.ident(&alias.name));
+        if !alias.columns.is_empty() {
+            docs.push(self.ident_list(&alias.columns));
+        }
+        Doc::Concat(docs)
+    }
+
+    fn data_type(&mut self, data_type: &DataType) -> Doc {
+        let mut words: Vec<Doc> = Vec::new();
+        for word in &data_type.name {
+            let text: String = self.options.data_type_case.apply(&word.value);
+            words.push(self.spanned(text, word.span));
+        }
+
+        let mut docs: Vec<Doc> = vec![Doc::join(words, Doc::text(" "))];
+        if !data_type.args.is_empty() {
+            docs.push(self.parenthesized(&data_type.args, Self::format_expr));
+        }
+        Doc::Concat(docs)
+    }
+
+    // ---- Layout helpers ----
+
+    // A clause keyword starting a line, followed by its body on the same line
+    // if it fits, or else indented on the lines below
+    fn clause(&mut self, keyword: &Keyword, format_body: impl FnOnce(&mut Self) -> Doc) -> Doc {
+        // Comments ahead of the keyword go before the group, so that they do
+        // not keep the clause from fitting on one line
+        let comments: Doc = self.comments_before(keyword.span.start.offset);
+        let keyword: Doc = self.keyword(keyword);
+        let body: Doc = format_body(self);
+        Doc::Concat(vec![
+            comments,
+            Doc::HardLine,
+            Doc::group(Doc::Concat(vec![
+                keyword,
+                Doc::indent(Doc::Concat(vec![Doc::Line, body])),
+            ])),
+        ])
+    }
+
+    // Separator between list items, breaking the line at `line` (a `Line` or
+    // `HardLine`) on the side of the comma the comma position asks for
+    fn comma(&self, line: Doc) -> Doc {
+        match self.options.comma_position {
+            CommaPosition::Trailing => Doc::Concat(vec![Doc::text(","), line]),
+            CommaPosition::Leading => {
+                // Flat, a leading comma still reads `a, b`
+                let line: Doc = if line == Doc::Line {
+                    Doc::SoftLine
+                } else {
+                    line
+                };
+                Doc::Concat(vec![line, Doc::Separator(String::from(", "))])
+            }
+        }
+    }
+
+    // Items separated by commas, breaking at `line` between them
+    fn comma_list<T>(
+        &mut self,
+        items: &[T],
+        line: Doc,
+        mut format_item: impl FnMut(&mut Self, &T) -> Doc,
+    ) -> Doc {
+        let mut docs: Vec<Doc> = Vec::with_capacity(items.len() * 3);
+        for (i, item) in items.iter().enumerate() {
+            if i > 0 {
+                docs.push(self.comma(line.clone()));
+            }
+            docs.push(format_item(self, item));
+        }
+        Doc::Concat(docs)
+    }
+
+    // Items separated by commas, all on one line if they fit or else one per line
+    fn block_list<T>(&mut self, items: &[T], format_item: impl FnMut(&mut Self, &T) -> Doc) -> Doc {
+        Doc::group(self.comma_list(items, Doc::Line, format_item))
+    }
+
+    // Items in parentheses, on one line if they fit or else one per line
+    // indented inside the parentheses
+    fn parenthesized<T>(
+        &mut self,
+        items: &[T],
+        format_item: impl FnMut(&mut Self, &T) -> Doc,
+    ) -> Doc {
+        self.delimited("(", ")", items, format_item)
+    }
+
+    // Items between `open` and `close`, laid out like a parenthesized list
+    fn delimited<T>(
+        &mut self,
+        open: &str,
+        close: &str,
+        items: &[T],
+        format_item: impl FnMut(&mut Self, &T) -> Doc,
+    ) -> Doc {
+        let items: Doc = self.comma_list(items, Doc::Line, format_item);
+        Doc::group(Doc::Concat(vec![
+            Doc::text(open),
+            Doc::indent(Doc::Concat(vec![Doc::SoftLine, items])),
+            Doc::SoftLine,
+            Doc::text(close),
+        ]))
+    }
+
+    // A query in parentheses as an indented block
+    fn subquery(&mut self, query: &Query) -> Doc {
+        let query: Doc = self.format_query(query);
+        Doc::Concat(vec![
+            Doc::text("("),
+            Doc::indent(Doc::Concat(vec![Doc::HardLine, query])),
+            Doc::HardLine,
+            Doc::text(")"),
+        ])
+    }
+
+    // ---- Statements ----
+
+    fn format_statement(&mut self, statement: &Statement) -> Doc {
+        match statement {
+            Statement::Query(query) => self.format_query(query),
+            Statement::Insert(insert) => self.format_insert(insert),
+            Statement::Update(update) => self.format_update(update),
+            Statement::Delete(delete) => self.format_delete(delete),
+            Statement::CreateTable(create) => self.format_create_table(create),
+            Statement::CreateView(create) => self.format_create_view(create),
+            Statement::Raw(tokens) => self.format_tokens(tokens),
+        }
+    }
+
+    fn format_insert(&mut self, insert: &Insert) -> Doc {
+        let mut docs: Vec<Doc> = vec![
+            self.keyword(&insert.keyword),
+            Doc::text(" "),
+            self.object_name(&insert.table),
+        ];
+        if !insert.columns.is_empty() {
+            docs.push(Doc::text(" "));
+            docs.push(self.ident_list(&insert.columns));
+        }
+        docs.push(Doc::HardLine);
+        docs.push(self.format_query(&insert.source));
+        if let Some(on_conflict) = &insert.on_conflict {
+            docs.push(self.format_on_conflict(on_conflict));
+        }
+        docs.push(self.returning(&insert.returning));
+        Doc::Concat(docs)
+    }
+
+    // The target and action on a line of their own, with the clauses of
+    // `DO UPDATE` indented below
+    fn format_on_conflict(&mut self, on_conflict: &OnConflict) -> Doc {
+        let comments: Doc = self.comments_before(on_conflict.keyword.span.start.offset);
+        let mut docs: Vec<Doc> = vec![comments, Doc::HardLine, self.keyword(&on_conflict.keyword)];
+        match &on_conflict.target {
+            ConflictTarget::Columns(columns) => {
+                docs.push(Doc::text(" "));
+                docs.push(self.parenthesized(columns, Self::format_expr));
+            }
+            ConflictTarget::Constraint(constraint) => {
+                docs.push(Doc::text(" "));
+                docs.push(self.keyword(&constraint.keyword));
+                docs.push(Doc::text(" "));
+                docs.push(self.object_name(&constraint.body));
+            }
+            ConflictTarget::None => {}
+        }
+
+        docs.push(Doc::text(" "));
+        match &on_conflict.action {
+            ConflictAction::Nothing(keyword) => docs.push(self.keyword(keyword)),
+            ConflictAction::Update(update) => {
+                docs.push(self.keyword(&update.keyword));
+                let assignments: Doc = self.clause(&update.assignments.keyword, |f| {
+                    f.block_list(&update.assignments.body, Self::format_assignment)
+                });
+                let selection: Doc = self.condition_clause(&update.selection);
+                docs.push(Doc::indent(Doc::Concat(vec![assignments, selection])));
+            }
+        }
+        Doc::Concat(docs)
+    }
+
+    fn format_update(&mut self, update: &Update) -> Doc {
+        Doc::Concat(vec![
+            self.clause(&update.keyword, |f| {
+                f.format_table_with_joins(&update.table)
+            }),
+            self.clause(&update.assignments.keyword, |f| {
+                f.block_list(&update.assignments.body, Self::format_assignment)
+            }),
+            self.from(&update.from),
+            self.condition_clause(&update.selection),
+            self.returning(&update.returning),
+        ])
+    }
+
+    fn format_assignment(&mut self, assignment: &Assignment) -> Doc {
+        Doc::Concat(vec![
+            self.object_name(&assignment.target),
+            Doc::text(" = "),
+            self.format_expr(&assignment.value),
+        ])
+    }
+
+    fn format_delete(&mut self, delete: &Delete) -> Doc {
+        Doc::Concat(vec![
+            self.clause(&delete.keyword, |f| f.format_table_factor(&delete.table)),
+            self.from(&delete.using),
+            self.condition_clause(&delete.selection),
+            self.returning(&delete.returning),
+        ])
+    }
+
+    fn format_create_table(&mut self, create: &CreateTable) -> Doc {
+        let mut docs: Vec<Doc> = vec![
+            self.keyword(&create.keyword),
+            Doc::text(" "),
+            self.object_name(&create.name),
+        ];
+
+        if !create.elements.is_empty() {
+            let elements: Doc =
+                self.comma_list(
+                    &create.elements,
+                    Doc::HardLine,
+                    |f, element| match element {
+                        TableElement::Column(column) => {
+                            let mut column_docs: Vec<Doc> = vec![
+                                f.ident(&column.name),
+                                Doc::text(" "),
+                                f.data_type(&column.data_type),
+                            ];
+                            if !column.options.is_empty() {
+                                column_docs.push(Doc::text(" "));
+                                column_docs.push(f.format_token_run(&column.options));
+                            }
+                            Doc::Concat(column_docs)
+                        }
+                        TableElement::Constraint(tokens) => f.format_token_run(tokens),
+                    },
+                );
+
+            docs.push(Doc::text(" ("));
+            docs.push(Doc::indent(Doc::Concat(vec![Doc::HardLine, elements])));
+            docs.push(Doc::HardLine);
+            docs.push(Doc::text(")"));
+        }
+
+        if let Some(query) = &create.query {
+            docs.push(Doc::text(" "));
+            docs.push(self.keyword(&query.keyword));
+            docs.push(Doc::HardLine);
+            docs.push(self.format_query(&query.body));
+        }
+        if !create.options.is_empty() {
+            docs.push(Doc::text(" "));
+            docs.push(self.format_token_run(&create.options));
+        }
+        Doc::Concat(docs)
+    }
+
+    fn format_create_view(&mut self, create: &CreateView) -> Doc {
+        let mut docs: Vec<Doc> = vec![
+            self.keyword(&create.keyword),
+            Doc::text(" "),
+            self.object_name(&create.name),
+        ];
+        if !create.columns.is_empty() {
+            docs.push(Doc::text(" "));
+            docs.push(self.ident_list(&create.columns));
+        }
+        docs.push(Doc::text(" "));
+        docs.push(self.keyword(&create.query.keyword));
+        docs.push(Doc::HardLine);
+        docs.push(self.format_query(&create.query.body));
+        Doc::Concat(docs)
+    }
+
+    // ---- Queries ----
+
+    fn format_query(&mut self, query: &Query) -> Doc {
+        let mut docs: Vec<Doc> = Vec::new();
+        if let Some(with) = &query.with {
+            docs.push(self.format_with(with));
+        }
+
+        docs.push(self.format_set_expr(&query.body));
+
+        if let Some(order_by) = &query.order_by {
+            docs.push(self.clause(&order_by.keyword, |f| {
+                f.block_list(&order_by.body, Self::format_order_by_expr)
+            }));
+        }
+        for clause in [&query.limit, &query.offset].into_iter().flatten() {
+            docs.push(self.clause(&clause.keyword, |f| f.format_expr(&clause.body)));
+        }
+        for lock in &query.locks {
+            docs.push(self.format_lock(lock));
+        }
+        Doc::Concat(docs)
+    }
+
+    fn format_lock(&mut self, lock: &Lock) -> Doc {
+        let comments: Doc = self.comments_before(lock.keyword.span.start.offset);
+        let mut docs: Vec<Doc> = vec![comments, Doc::HardLine, self.keyword(&lock.keyword)];
+        if let Some(of) = &lock.of {
+            docs.push(Doc::text(" "));
+            docs.push(self.keyword(&of.keyword));
+            docs.push(Doc::text(" "));
+            docs.push(self.block_list(&of.body, Self::object_name));
+        }
+        if let Some(wait) = &lock.wait {
+            docs.push(Doc::text(" "));
+            docs.push(self.keyword(wait));
+        }
+        Doc::Concat(docs)
+    }
+
+    fn format_with(&mut self, with: &With) -> Doc {
+        let keyword: Doc = self.keyword(&with.keyword);
+        let ctes: Doc = self.comma_list(&with.ctes, Doc::HardLine, |f, cte| {
+            let mut docs: Vec<Doc> = vec![f.ident(&cte.name)];
+            if !cte.columns.is_empty() {
+                docs.push(f.ident_list(&cte.columns));
+            }
+            docs.push(Doc::text(" "));
+            docs.push(f.keyword(&cte.query.keyword));
+            docs.push(Doc::text(" "));
+            docs.push(f.subquery(&cte.query.body));
+            Doc::Concat(docs)
+        });
+
+        Doc::Concat(vec![keyword, Doc::text(" "), ctes, Doc::HardLine])
+    }
+
+    fn format_set_expr(&mut self, expr: &SetExpr) -> Doc {
+        match expr {
+            SetExpr::Select(select) => self.format_select(select),
+            SetExpr::Values(values) => self.clause(&values.keyword, |f| {
+                f.block_list(&values.rows, |f, row| {
+                    f.parenthesized(row, Self::format_expr)
+                })
+            }),
+            SetExpr::Query(query) => Doc::Concat(vec![Doc::HardLine, self.subquery(query)]),
+            SetExpr::SetOperation {
+                left,
+                operator,
+                right,
+            } => Doc::Concat(vec![
+                self.format_set_expr(left),
+                Doc::HardLine,
+                self.keyword(operator),
+                Doc::HardLine,
+                self.format_set_expr(right),
+            ]),
+        }
+    }
+
+    fn format_select(&mut self, select: &Select) -> Doc {
+        let comments: Doc = self.comments_before(select.keyword.span.start.offset);
+        let mut head: Vec<Doc> = vec![self.keyword(&select.keyword)];
+        if let Some(quantifier) = &select.quantifier {
+            head.push(Doc::text(" "));
+            head.push(self.keyword(quantifier));
+        }
+        if let Some(distinct_on) = &select.distinct_on {
+            head.push(Doc::text(" "));
+            head.push(self.keyword(&distinct_on.keyword));
+            head.push(Doc::text(" "));
+            head.push(self.parenthesized(&distinct_on.body, Self::format_expr));
+        }
+        let projection: Doc = self.block_list(&select.projection, Self::format_select_item);
+        head.push(Doc::indent(Doc::Concat(vec![Doc::Line, projection])));
+
+        let mut docs: Vec<Doc> = vec![comments, Doc::HardLine, Doc::group(Doc::Concat(head))];
+        docs.push(self.from(&select.from));
+        docs.push(self.condition_clause(&select.selection));
+        if let Some(group_by) = &select.group_by {
+            docs.push(self.clause(&group_by.keyword, |f| {
+                f.block_list(&group_by.body, Self::format_expr)
+            }));
+        }
+        docs.push(self.condition_clause(&select.having));
+        Doc::Concat(docs)
+    }
+
+    fn format_select_item(&mut self, item: &SelectItem) -> Doc {
+        Doc::Concat(vec![self.format_expr(&item.expr), self.alias(&item.alias)])
+    }
+
+    fn from(&mut self, from: &Option<Clause<Vec<TableWithJoins>>>) -> Doc {
+        match from {
+            Some(from) => self.clause(&from.keyword, |f| {
+                f.block_list(&from.body, Self::format_table_with_joins)
+            }),
+            None => Doc::nil(),
+        }
+    }
+
+    // A WHERE or HAVING clause, with one AND/OR condition per line when it
+    // does not fit on one
+    fn condition_clause(&mut self, clause: &Option<Clause<Expr>>) -> Doc {
+        match clause {
+            Some(clause) => self.clause(&clause.keyword, |f| f.format_condition(&clause.body)),
+            None => Doc::nil(),
+        }
+    }
+
+    fn returning(&mut self, returning: &Option<Clause<Vec<SelectItem>>>) -> Doc {
+        match returning {
+            Some(returning) => self.clause(&returning.keyword, |f| {
+                f.block_list(&returning.body, Self::format_select_item)
+            }),
+            None => Doc::nil(),
+        }
+    }
+
+    // AND/OR operands separated by lines that break with the enclosing group
+    fn format_condition(&mut self, expr: &Expr) -> Doc {
+        match expr {
+            Expr::OperatorChain { first, rest }
+                if rest.first().is_some_and(|(op, _)| {
+                    op.text.eq_ignore_ascii_case("AND") || op.text.eq_ignore_ascii_case("OR")
+                }) =>
+            {
+                let mut docs: Vec<Doc> = vec![self.format_condition(first)];
+                for (op, operand) in rest {
+                    docs.extend([
+                        Doc::Line,
+                        self.operator(op),
+                        Doc::text(" "),
+                        self.format_condition(operand),
+                    ]);
+                }
+                Doc::Concat(docs)
+            }
+            _ => self.format_expr(expr),
+        }
+    }
+
+    fn format_order_by_expr(&mut self, order_by: &OrderByExpr) -> Doc {
+        let mut docs: Vec<Doc> = vec![self.format_expr(&order_by.expr)];
+        for option in &order_by.options {
+            docs.push(Doc::text(" "));
+            docs.push(self.keyword(option));
+        }
+        Doc::Concat(docs)
+    }
+
+    // ---- Table references ----
+
+    fn format_table_with_joins(&mut self, table: &TableWithJoins) -> Doc {
+        let mut docs: Vec<Doc> = vec![self.format_table_factor(&table.relation)];
+
+        for join in &table.joins {
+            docs.push(Doc::HardLine);
+            docs.push(self.keyword(&join.operator));
+            docs.push(Doc::text(" "));
+            docs.push(self.format_table_factor(&join.relation));
+
+            match &join.constraint {
+                JoinConstraint::On(on) => {
+                    docs.push(Doc::text(" "));
+                    docs.push(self.keyword(&on.keyword));
+                    docs.push(Doc::text(" "));
+                    // Further AND/OR conditions continue on lines of their own
+                    // when the join does not fit on one
+                    let condition: Doc = self.format_condition(&on.body);
+                    docs.push(Doc::group(Doc::indent(condition)));
+                }
+                JoinConstraint::Using(using) => {
+                    docs.push(Doc::text(" "));
+                    docs.push(self.keyword(&using.keyword));
+                    docs.push(Doc::text(" "));
+                    docs.push(self.ident_list(&using.body));
+                }
+                JoinConstraint::None => {}
+            }
+        }
+        Doc::Concat(docs)
+    }
+
+    fn format_table_factor(&mut self, table: &TableFactor) -> Doc {
+        match table {
+            TableFactor::Table { name, args, alias } => {
+                let mut docs: Vec<Doc> = vec![self.object_name(name)];
+                if let Some(args) = args {
+                    docs.push(self.parenthesized(args, Self::format_expr));
+                }
+                docs.push(self.alias(alias));
+                Doc::Concat(docs)
+            }
+            TableFactor::Derived {
+                lateral,
+                subquery,
+                alias,
+            } => {
+                let mut docs: Vec<Doc> = Vec::new();
+                if let Some(lateral) = lateral {
+                    docs.push(self.keyword(lateral));
+                    docs.push(Doc::text(" "));
+                }
+                docs.push(self.subquery(subquery));
+                docs.push(self.alias(alias));
+                Doc::Concat(docs)
+            }
+            TableFactor::NestedJoin { table, alias } => {
+                let table: Doc = self.format_table_with_joins(table);
+                Doc::Concat(vec![
+                    Doc::text("("),
+                    Doc::indent(Doc::Concat(vec![Doc::HardLine, table])),
+                    Doc::HardLine,
+                    Doc::text(")"),
+                    self.alias(alias),
+                ])
+            }
+        }
+    }
+
+    // ---- Expressions ----
+
+    fn format_expr(&mut self, expr: &Expr) -> Doc {
+        match expr {
+            Expr::Identifier(name) => self.object_name(name),
+            Expr::Wildcard { qualifier, span } => {
+                let mut docs: Vec<Doc> = Vec::new();
+                if let Some(qualifier) = qualifier {
+                    docs.push(self.object_name(qualifier));
+                    docs.push(Doc::text("."));
+                }
+                docs.push(self.spanned(String::from("*"), *span));
+                Doc::Concat(docs)
+            }
+            Expr::Literal(literal) | Expr::Parameter(literal) => {
+                self.spanned(literal.value.clone(), literal.span)
+            }
+            Expr::Keyword(keyword) => self.keyword(keyword),
+            Expr::TypedString { data_type, value } => Doc::Concat(vec![
+                self.data_type(data_type),
+                Doc::text(" "),
+                self.spanned(value.value.clone(), value.span),
+            ]),
+            // Long operands break before the operator, indented below the left one
+            Expr::BinaryOp { left, op, right } => {
+                // A subquery goes right after the operator, laying out a block
+                // at the indent of the left operand
+                if matches!(**right, Expr::Subquery(_)) {
+                    return Doc::Concat(vec![
+                        self.format_expr(left),
+                        Doc::text(" "),
+                        self.operator(op),
+                        Doc::text(" "),
+                        self.format_expr(right),
+                    ]);
+                }
+                // The operator goes right after the `END` of a CASE or the `)`
+                // of a subquery, which close layouts of their own
+                let line: Doc = if matches!(**left, Expr::Case(_) | Expr::Subquery(_)) {
+                    Doc::text(" ")
+                } else {
+                    Doc::Line
+                };
+                let left: Doc = self.format_expr(left);
+                let op: Doc = self.operator(op);
+                let right: Doc = self.format_expr(right);
+                Doc::group(Doc::Concat(vec![
+                    left,
+                    Doc::indent(Doc::Concat(vec![line, op, Doc::text(" "), right])),
+                ]))
+            }
+            Expr::OperatorChain { first, rest } => self.format_chain(first, rest),
+            Expr::UnaryOp { op, expr } => {
+                let mut docs: Vec<Doc> = vec![self.operator(op)];
+                if op.text.chars().all(char::is_alphabetic) {
+                    docs.push(Doc::text(" "));
+                }
+                docs.push(self.format_expr(expr));
+                Doc::Concat(docs)
+            }
+            Expr::Nested(expr) => {
+                Doc::Concat(vec![Doc::text("("), self.format_expr(expr), Doc::text(")")])
+            }
+            Expr::Tuple(items) => self.parenthesized(items, Self::format_expr),
+            Expr::Function(function) => self.format_function(function),
+            Expr::Subquery(query) => self.subquery(query),
+            Expr::Exists { keyword, subquery } => Doc::Concat(vec![
+                self.keyword(keyword),
+                Doc::text(" "),
+                self.subquery(subquery),
+            ]),
+            Expr::InList {
+                expr,
+                keyword,
+                list,
+            } => Doc::Concat(vec![
+                self.format_expr(expr),
+                Doc::text(" "),
+                self.keyword(keyword),
+                Doc::text(" "),
+                self.parenthesized(list, Self::format_expr),
+            ]),
+            Expr::InSubquery {
+                expr,
+                keyword,
+                subquery,
+            } => Doc::Concat(vec![
+                self.format_expr(expr),
+                Doc::text(" "),
+                self.keyword(keyword),
+                Doc::text(" "),
+                self.subquery(subquery),
+            ]),
+            Expr::Between {
+                expr,
+                keyword,
+                low,
+                high,
+            } => Doc::Concat(vec![
+                self.format_expr(expr),
+                Doc::text(" "),
+                self.keyword(keyword),
+                Doc::text(" "),
+                self.format_expr(low),
+                self.keyword_text(" AND "),
+                self.format_expr(high),
+            ]),
+            Expr::Case(case) => self.format_case(case),
+            Expr::Cast {
+                keyword,
+                expr,
+                as_keyword,
+                data_type,
+            } => Doc::Concat(vec![
+                self.keyword(keyword),
+                Doc::text("("),
+                self.format_expr(expr),
+                Doc::text(" "),
+                self.keyword(as_keyword),
+                Doc::text(" "),
+                self.data_type(data_type),
+                Doc::text(")"),
+            ]),
+            Expr::DoubleColonCast { expr, data_type } => Doc::Concat(vec![
+                self.format_expr(expr),
+                Doc::text("::"),
+                self.data_type(data_type),
+            ]),
+            Expr::PathAccess { expr, path } => Doc::Concat(vec![
+                self.format_expr(expr),
+                Doc::text(":"),
+                self.object_name(path),
+            ]),
+            Expr::Subscript { expr, subscript } => {
+                let mut docs: Vec<Doc> = vec![self.format_expr(expr), Doc::text("[")];
+                match &**subscript {
+                    Subscript::Index(index) => docs.push(self.format_expr(index)),
+                    Subscript::Slice { lower, upper } => {
+                        if let Some(lower) = lower {
+                            docs.push(self.format_expr(lower));
+                        }
+                        docs.push(Doc::text(":"));
+                        if let Some(upper) = upper {
+                            docs.push(self.format_expr(upper));
+                        }
+                    }
+                }
+                docs.push(Doc::text("]"));
+                Doc::Concat(docs)
+            }
+            Expr::Array { keyword, elements } => {
+                let mut docs: Vec<Doc> = Vec::new();
+                if let Some(keyword) = keyword {
+                    docs.push(self.keyword(keyword));
+                }
+                docs.push(self.delimited("[", "]", elements, Self::format_expr));
+                Doc::Concat(docs)
+            }
+        }
+    }
+
+    // Long chains break before every operator, indented below the first
+    // operand. A subquery goes right after its operator, laying out a block
+    // at the indent of the first operand, and ends the group before it.
+    fn format_chain(&mut self, first: &Expr, rest: &[(Operator, Expr)]) -> Doc {
+        let mut docs: Vec<Doc> = Vec::new();
+        let mut head: Doc = self.format_expr(first);
+        let mut links: Vec<Doc> = Vec::new();
+        let mut previous: &Expr = first;
+
+        for (op, operand) in rest {
+            let op: Doc = self.operator(op);
+            if matches!(operand, Expr::Subquery(_)) {
+                docs.push(Doc::group(Doc::Concat(vec![
+                    head,
+                    Doc::indent(Doc::Concat(std::mem::take(&mut links))),
+                ])));
+                head = Doc::Concat(vec![
+                    Doc::text(" "),
+                    op,
+                    Doc::text(" "),
+                    self.format_expr(operand),
+                ]);
+            } else {
+                // The operator goes right after the `END` of a CASE or the
+                // `)` of a subquery, which close layouts of their own
+                let line: Doc = if matches!(previous, Expr::Case(_) | Expr::Subquery(_)) {
+                    Doc::text(" ")
+                } else {
+                    Doc::Line
+                };
+                links.extend([line, op, Doc::text(" "), self.format_expr(operand)]);
+            }
+            previous = operand;
+        }
+
+        docs.push(Doc::group(Doc::Concat(vec![
+            head,
+            Doc::indent(Doc::Concat(links)),
+        ])));
+        Doc::Concat(docs)
+    }
+
+    // Lays out each `WHEN ... THEN` and the `ELSE` on a line of their own,
+    // one level deeper than the `CASE` and the `END` that closes it. With
+    // `inline_short_case`, a CASE that fits on the line stays on it.
+    fn format_case(&mut self, case: &Case) -> Doc {
+        let mut head: Vec<Doc> = vec![self.keyword(&case.keyword)];
+        if let Some(operand) = &case.operand {
+            head.push(Doc::text(" "));
+            head.push(self.format_expr(operand));
+        }
+
+        let line: Doc = if self.options.inline_short_case {
+            Doc::Line
+        } else {
+            Doc::HardLine
+        };
+        let mut branches: Vec<Doc> = Vec::new();
+        for when in &case.conditions {
+            branches.extend([
+                line.clone(),
+                self.keyword(&when.when),
+                Doc::text(" "),
+                self.format_expr(&when.condition),
+                Doc::text(" "),
+                self.keyword(&when.then),
+                Doc::text(" "),
+                self.format_expr(&when.result),
+            ]);
+        }
+        if let Some(else_result) = &case.else_result {
+            branches.extend([
+                line.clone(),
+                self.keyword(&else_result.keyword),
+                Doc::text(" "),
+                self.format_expr(&else_result.body),
+            ]);
+        }
+
+        let end: Doc = self.keyword(&case.end);
+        Doc::group(Doc::Concat(vec![
+            Doc::Concat(head),
+            Doc::indent(Doc::Concat(branches)),
+            line,
+            end,
+        ]))
+    }
+
+    fn format_function(&mut self, function: &Function) -> Doc {
+        let mut docs: Vec<Doc> = vec![match function.name.0.as_slice() {
+            [name]
+                if BUILTIN_FUNCTIONS
+                    .iter()
+                    .any(|builtin| builtin.eq_ignore_ascii_case(&name.value)) =>
+            {
+                let text: String = self.options.function_case.apply(&name.value);
+                self.spanned(text, name.span)
+            }
+            _ => self.object_name(&function.name),
+        }];
+
+        match &function.args {
+            FunctionArgs::List {
+                quantifier,
+                args,
+                order_by,
+            } => {
+                let mut args_docs: Vec<Doc> = Vec::new();
+                if let Some(quantifier) = quantifier {
+                    args_docs.push(self.keyword(quantifier));
+                    args_docs.push(Doc::text(" "));
+                }
+                args_docs.push(self.comma_list(args, Doc::Line, Self::format_expr));
+                if !order_by.is_empty() {
+                    args_docs.push(self.keyword_text(" ORDER BY "));
+                    let order_by: Vec<Doc> = order_by
+                        .iter()
+                        .map(|item| self.format_order_by_expr(item))
+                        .collect();
+                    args_docs.push(Doc::join(order_by, Doc::text(", ")));
+                }
+
+                docs.push(Doc::group(Doc::Concat(vec![
+                    Doc::text("("),
+                    Doc::indent(Doc::Concat(vec![Doc::SoftLine, Doc::Concat(args_docs)])),
+                    Doc::SoftLine,
+                    Doc::text(")"),
+                ])));
+            }
+            FunctionArgs::Raw(tokens) => {
+                docs.push(Doc::text("("));
+                docs.push(self.format_token_run(tokens));
+                docs.push(Doc::text(")"));
+            }
+        }
+
+        if let Some(filter) = &function.filter {
+            docs.push(self.keyword_text(" FILTER (WHERE "));
+            docs.push(self.format_expr(filter));
+            docs.push(Doc::text(")"));
+        }
+
+        match &function.over {
+            Some(WindowSpec::Named(name)) => {
+                docs.push(self.keyword_text(" OVER "));
+                docs.push(self.ident(name));
+            }
+            Some(WindowSpec::Inline {
+                partition_by,
+                order_by,
+                frame,
+            }) => {
+                let mut parts: Vec<Doc> = Vec::new();
+                if !partition_by.is_empty() {
+                    let items: Vec<Doc> = partition_by
+                        .iter()
+                        .map(|expr| self.format_expr(expr))
+                        .collect();
+                    parts.push(Doc::Concat(vec![
+                        self.keyword_text("PARTITION BY "),
+                        Doc::join(items, Doc::text(", ")),
+                    ]));
+                }
+                if !order_by.is_empty() {
+                    let items: Vec<Doc> = order_by
+                        .iter()
+                        .map(|item| self.format_order_by_expr(item))
+                        .collect();
+                    parts.push(Doc::Concat(vec![
+                        self.keyword_text("ORDER BY "),
+                        Doc::join(items, Doc::text(", ")),
+                    ]));
+                }
+                if !frame.is_empty() {
+                    parts.push(self.format_token_run(frame));
+                }
+
+                docs.push(self.keyword_text(" OVER "));
+                docs.push(Doc::group(Doc::Concat(vec![
+                    Doc::text("("),
+                    Doc::indent(Doc::Concat(vec![
+                        Doc::SoftLine,
+                        Doc::join(parts, Doc::Line),
+                    ])),
+                    Doc::SoftLine,
+                    Doc::text(")"),
+                ])));
+            }
+            None => {}
+        }
+        Doc::Concat(docs)
+    }
+
+    // ---- Tokens ----
+
+    fn token_text(&self, token: &Token) -> String {
+        match token {
+            Token::Keyword(s) => self.options.keyword_case.apply(s),
+            Token::Identifier(s) => self.identifier_text(s),
+            Token::QuotedIdentifier(s)
+            | Token::Stage(s)
+            | Token::Literal(s)
+            | Token::Parameter(s)
+            | Token::Operator(s)
+            | Token::Invalid(s) => s.clone(),
+            Token::Punctuation(c) => c.to_string(),
+            Token::Comment(comment) => comment.text.clone(),
+            Token::DollarQuoted(quoted) => quoted.text(),
+            Token::Whitespace | Token::Eof => String::new(),
+        }
+    }
+
+    // Whether a space belongs between two adjacent tokens on a line
+    fn needs_space(&self, last: &Token, token: &Token) -> bool {
+        match (last, token) {
+            (Token::Punctuation('(' | '[' | '.'), _) => false,
+            (_, Token::Punctuation('.' | ')' | ']' | ',')) => false,
+            // Subscripts and array constructors, e.g. `arr[1]` and `ARRAY[1]`
+            (
+                Token::Identifier(_) | Token::QuotedIdentifier(_) | Token::Punctuation(')' | ']'),
+                Token::Punctuation('['),
+            ) => false,
+            (Token::Keyword(word), Token::Punctuation('[')) => {
+                self.options.dialect.dialect().keywords().is_reserved(word)
+            }
+            // Casts and paths into semi-structured data, e.g. `payload:id`
+            (Token::Operator(op), _) | (_, Token::Operator(op)) if op == "::" || op == ":" => false,
+            (Token::Identifier(_) | Token::QuotedIdentifier(_), Token::Punctuation('(')) => false,
+            // Non-reserved keywords before `(` are mostly function-like, e.g. `CAST(`
+            (Token::Keyword(word), Token::Punctuation('(')) => {
+                self.options.dialect.dialect().keywords().is_reserved(word)
+            }
+            _ => true,
+        }
+    }
+
+    // A run of tokens kept on one line, e.g. column constraints
+    fn format_token_run(&mut self, tokens: &[SpannedToken]) -> Doc {
+        let mut docs: Vec<Doc> = Vec::new();
+        let mut last_token: Option<&Token> = None;
+        for SpannedToken { token, span } in tokens {
+            if last_token.is_some_and(|last| self.needs_space(last, token)) {
+                docs.push(Doc::text(" "));
+            }
+            let text: String = self.token_text(token);
+            docs.push(self.spanned(text, *span));
+            last_token = Some(token);
+        }
+        Doc::Concat(docs)
+    }
+
+    // Lays out a statement the parser did not understand token by token,
+    // breaking lines around clause keywords, joins, parentheses and commas the
+    // way a parsed statement breaks them. A clause keyword is followed by the
+    // clause body on its line if it fits, or else indented under it until the
+    // next clause or the `)` around it, so nested queries come out as nested
+    // blocks.
+    fn format_tokens(&mut self, tokens: &[SpannedToken]) -> Doc {
+        let kind: RawStatement = RawStatement::of(tokens);
+        let mut levels: IndentLevels = IndentLevels::new();
+        let mut last_token: Option<&Token> = None;
+        // Whether the last token ended a compound keyword, which is spaced
+        // from a `(` after it like a reserved word is
+        let mut after_compound: bool = false;
+
+        let mut index: usize = 0;
+        while let Some(SpannedToken { token, .. }) = tokens.get(index) {
+            // A compound keyword such as `GROUP BY` is laid out as one word
+            let len: usize = match token {
+                Token::Keyword(_) => keywords::compound_len(|n| match tokens.get(index + n) {
+                    Some(SpannedToken {
+                        token: Token::Keyword(word) | Token::Identifier(word),
+                        ..
+                    }) => Some(word.as_str()),
+                    _ => None,
+                })
+                .unwrap_or(1),
+                _ => 1,
+            };
+            let words: &[SpannedToken] = &tokens[index..index + len];
+            index += len;
+            let follows_compound: bool = std::mem::replace(&mut after_compound, len > 1);
+
+            let source: Vec<&str> = words
+                .iter()
+                .filter_map(|word| match &word.token {
+                    Token::Keyword(word) | Token::Identifier(word) => Some(word.as_str()),
+                    _ => None,
+                })
+                .collect();
+            let keyword: String = source.join(" ");
+            let upper: String = keyword.to_uppercase();
+            let is_keyword: bool = matches!(token, Token::Keyword(_));
+            // Outside parentheses and CASE, some statements give keywords a
+            // meaning of their own
+            let nested: bool = levels.is_open(Scope::Parenthesis) || levels.is_open(Scope::Case);
+            let kind: RawStatement = if nested { RawStatement::Other } else { kind };
+            let raw_clause: bool = RAW_CLAUSE_KEYWORDS.contains(&upper.as_str());
+            let is_clause: bool = is_keyword
+                && match kind {
+                    RawStatement::Privileges => index == len,
+                    RawStatement::Merge => upper == "USING",
+                    RawStatement::Alter => raw_clause && upper != "SET",
+                    RawStatement::Other => raw_clause,
+                };
+            let is_branch: bool = is_keyword && kind == RawStatement::Merge && upper == "WHEN";
+            // `LEFT(` and `RIGHT(` are the string functions
+            let is_join: bool = is_keyword
+                && matches!(kind, RawStatement::Alter | RawStatement::Other)
+                && RAW_JOIN_KEYWORDS.contains(&upper.as_str())
+                && !(matches!(upper.as_str(), "LEFT" | "RIGHT")
+                    && tokens.get(index).map(|next| &next.token) == Some(&Token::Punctuation('(')));
+            let is_set_operator: bool = is_keyword && RAW_SET_OPERATORS.contains(&upper.as_str());
+            let in_case: bool = levels.scope() == Scope::Case;
+            let opens_case: bool = is_keyword
+                && upper == "CASE"
+                && !matches!(last_token, Some(Token::Keyword(word)) if word.eq_ignore_ascii_case("END"));
+            let case_line: Doc = if self.options.inline_short_case {
+                Doc::Line
+            } else {
+                Doc::HardLine
+            };
+
+            // A new clause, branch, query or statement ends the clause before
+            // it, a join ends the join before it, and comments ahead of either
+            // go with it
+            if is_clause || is_branch || is_set_operator || *token == Token::Punctuation(';') {
+                levels.close_clauses();
+            } else if is_join {
+                levels.close_join();
+            }
+            let comments: Doc = self.comments_before(words[len - 1].span.start.offset);
+            levels.current().push(comments);
+
+            match token {
+                // The clause body goes after the line break, which carries
+                // its own spacing
+                Token::Keyword(_) if is_clause => {
+                    let keyword: Doc = self.keyword_text(&keyword);
+                    levels.current().push(Doc::HardLine);
+                    levels.open(Scope::Clause, keyword, vec![Doc::Line]);
+                    last_token = None;
+                    continue;
+                }
+                Token::Keyword(_) if is_join || is_branch => {
+                    let keyword: Doc = self.keyword_text(&keyword);
+                    levels.current().push(Doc::HardLine);
+                    levels.open(Scope::Join, keyword, Vec::new());
+                }
+                // The action of a MERGE branch goes on below its condition
+                // when the branch does not fit on one line
+                Token::Keyword(_)
+                    if upper == "THEN"
+                        && kind == RawStatement::Merge
+                        && levels.scope() == Scope::Join =>
+                {
+                    let keyword: Doc = self.keyword_text(&keyword);
+                    levels
+                        .current()
+                        .extend([Doc::text(" "), keyword, Doc::Line]);
+                    last_token = None;
+                    continue;
+                }
+                Token::Keyword(_) if is_set_operator => {
+                    let keyword: Doc = self.keyword_text(&keyword);
+                    levels
+                        .current()
+                        .extend([Doc::HardLine, keyword, Doc::HardLine]);
+                }
+                // CASE is laid out as in a parsed statement
+                Token::Keyword(_) if opens_case => {
+                    let doc: Doc = self.keyword_with_space(&keyword, token, last_token);
+                    levels.open(Scope::Case, doc, Vec::new());
+                }
+                Token::Keyword(_) if in_case && (upper == "WHEN" || upper == "ELSE") => {
+                    let keyword: Doc = self.keyword_text(&keyword);
+                    levels.current().extend([case_line, keyword]);
+                }
+                Token::Keyword(_) if in_case && upper == "END" => {
+                    let keyword: Doc = self.keyword_text(&keyword);
+                    levels.close_delimited(Scope::Case, Doc::Concat(vec![case_line, keyword]));
+                }
+                // Conditions inside CASE stay on the line of their WHEN
+                Token::Keyword(_) if (upper == "AND" || upper == "OR") && !in_case => {
+                    let keyword: Doc = self.keyword_text(&keyword);
+                    let line: Doc = if levels.scope() == Scope::Statement {
+                        Doc::HardLine
+                    } else {
+                        Doc::Line
+                    };
+                    levels.current().extend([line, keyword]);
+                }
+                Token::Keyword(_) | Token::Identifier(_)
+                    if len == 1 && Self::is_raw_data_type(tokens, index - 1) =>
+                {
+                    let space: bool = last_token.is_some_and(|last| self.needs_space(last, token));
+                    let name: String = self.options.data_type_case.apply(&keyword);
+                    levels.current().push(Doc::Text(if space {
+                        format!(" {}", name)
+                    } else {
+                        name
+                    }));
+                }
+                Token::Keyword(_) => {
+                    let start: usize = index - len;
+                    let doc: Doc = if len == 1 && self.is_raw_name(tokens, start) {
+                        let space: bool =
+                            last_token.is_some_and(|last| self.needs_space(last, token));
+                        let name: String = self.identifier_text(&keyword);
+                        Doc::Text(if space { format!(" {}", name) } else { name })
+                    } else {
+                        self.keyword_with_space(&keyword, token, last_token)
+                    };
+                    levels.current().push(doc);
+                }
+                Token::Punctuation('(') => {
+                    let doc: Doc = if follows_compound {
+                        Doc::text(" (")
+                    } else {
+                        self.token_with_space(token, last_token)
+                    };
+                    levels.open(Scope::Parenthesis, doc, vec![Doc::SoftLine]);
+                }
+                // Closes the clauses opened inside the parenthesis along with
+                // it; an unmatched `)` closes nothing
+                Token::Punctuation(')') => {
+                    let close: Doc = Doc::Concat(vec![Doc::SoftLine, Doc::text(")")]);
+                    if !levels.close_delimited(Scope::Parenthesis, close) {
+                        levels.current().extend([Doc::HardLine, Doc::text(")")]);
+                    }
+                }
+                Token::Punctuation('[') => {
+                    let doc: Doc = self.token_with_space(token, last_token);
+                    levels.open(Scope::Bracket, doc, vec![Doc::SoftLine]);
+                }
+                Token::Punctuation(']') => {
+                    let close: Doc = Doc::Concat(vec![Doc::SoftLine, Doc::text("]")]);
+                    if !levels.close_delimited(Scope::Bracket, close) {
+                        levels.current().push(Doc::text("]"));
+                    }
+                }
+                // The separator carries its own spacing, so the next token
+                // goes right after it. Lists only break when they do not fit,
+                // except between the clauses of a statement.
+                Token::Punctuation(',') => {
+                    let line: Doc = if levels.scope() == Scope::Statement {
+                        Doc::HardLine
+                    } else {
+                        Doc::Line
+                    };
+                    let comma: Doc = self.comma(line);
+                    levels.current().push(comma);
+                    last_token = None;
+                    continue;
+                }
+                Token::DollarQuoted(quoted) => {
+                    // After `AS` or `DO`, the string is a function or `DO` block
+                    // body, which is only SQL in a `LANGUAGE sql` function
+                    let is_body: bool = matches!(
+                        last_token,
+                        Some(Token::Keyword(word))
+                            if word.eq_ignore_ascii_case("AS") || word.eq_ignore_ascii_case("DO")
+                    );
+                    let is_sql: bool = Self::body_language(tokens)
+                        .is_some_and(|language| language.eq_ignore_ascii_case("sql"));
+                    let space: bool = last_token.is_some_and(|last| self.needs_space(last, token));
+                    let doc: Doc = self.dollar_quoted(quoted, is_body && is_sql);
+                    let level: &mut Vec<Doc> = levels.current();
+                    if space {
+                        level.push(Doc::text(" "));
+                    }
+                    level.push(doc);
+                }
+                // Ends a statement inside a procedural body
+                Token::Punctuation(';') => {
+                    levels.current().extend([Doc::text(";"), Doc::HardLine]);
+                }
+                _ => {
+                    let doc: Doc = self.token_with_space(token, last_token);
+                    levels.current().push(doc);
+                }
+            }
+            last_token = Some(&words[len - 1].token);
+        }
+
+        levels.finish()
+    }
+
+    // Whether the word at `index` of a raw statement is part of a data type:
+    // a built-in type name after a column name, `::`, `TYPE` or `RETURNS`, or
+    // a word that goes on the type before it, e.g. `varying` in `character
+    // varying`
+    fn is_raw_data_type(tokens: &[SpannedToken], index: usize) -> bool {
+        let word = |index: usize| match tokens.get(index).map(|spanned| &spanned.token) {
+            Some(Token::Keyword(word) | Token::Identifier(word)) => Some(word.to_uppercase()),
+            _ => None,
+        };
+        let Some(this) = word(index) else {
+            return false;
+        };
+        if index == 0 {
+            return false;
+        }
+
+        let type_position: bool = match &tokens[index - 1].token {
+            Token::Identifier(_) | Token::QuotedIdentifier(_) => true,
+            Token::Operator(op) => op == "::",
+            Token::Keyword(previous) => {
+                previous.eq_ignore_ascii_case("TYPE") || previous.eq_ignore_ascii_case("RETURNS")
+            }
+            _ => false,
+        };
+        if keywords::is_data_type(&this) && type_position {
+            return true;
+        }
+        match this.as_str() {
+            "PRECISION" | "VARYING" | "UNSIGNED" => Self::is_raw_data_type(tokens, index - 1),
+            "WITH" | "WITHOUT" => {
+                word(index + 1).as_deref() == Some("TIME")
+                    && word(index + 2).as_deref() == Some("ZONE")
+                    && Self::is_raw_data_type(tokens, index - 1)
+            }
+            "TIME" => {
+                matches!(word(index - 1).as_deref(), Some("WITH" | "WITHOUT"))
+                    && Self::is_raw_data_type(tokens, index - 1)
+            }
+            "ZONE" => {
+                word(index - 1).as_deref() == Some("TIME")
+                    && Self::is_raw_data_type(tokens, index - 1)
+            }
+            _ => false,
+        }
+    }
+
+    // Whether the non-reserved keyword at `index` of a raw statement stands
+    // where a name goes, e.g. `data` in `UPDATE data SET value = 1`: after a
+    // clause keyword, a comma, a parenthesis or an operator unless another
+    // keyword phrase goes on (`ON DUPLICATE KEY`), or between a name or value
+    // and punctuation. A word ending a known keyword phrase, such as
+    // `MATCHED` in `WHEN MATCHED`, is never a name.
+    fn is_raw_name(&self, tokens: &[SpannedToken], index: usize) -> bool {
+        let keywords: &KeywordSet = self.options.dialect.dialect().keywords();
+        let Token::Keyword(word) = &tokens[index].token else {
+            return false;
+        };
+        if index == 0 || keywords.is_reserved(word) {
+            return false;
+        }
+        let ends_keyword_phrase: bool = RAW_KEYWORD_PHRASES.iter().any(|phrase| {
+            phrase.len() <= index + 1
+                && phrase.iter().rev().enumerate().all(|(n, keyword)| {
+                    matches!(&tokens[index - n].token, Token::Keyword(word) if word.eq_ignore_ascii_case(keyword))
+                })
+        });
+        if ends_keyword_phrase {
+            return false;
+        }
+        let ends_phrase = |word: &str| {
+            let word: String = word.to_uppercase();
+            RAW_CLAUSE_KEYWORDS.contains(&word.as_str())
+                || RAW_JOIN_KEYWORDS.contains(&word.as_str())
+                || RAW_NAME_KEYWORDS.contains(&word.as_str())
+        };
+
+        let previous: &Token = &tokens[index - 1].token;
+        let next: Option<&Token> = tokens.get(index + 1).map(|next| &next.token);
+        let after_name_position: bool = match previous {
+            Token::Keyword(previous) => ends_phrase(previous),
+            Token::Punctuation(',' | '(' | '[' | '.') | Token::Operator(_) => true,
+            _ => false,
+        };
+        let phrase_goes_on: bool = matches!(next, Some(Token::Keyword(next)) if !ends_phrase(next));
+        let before_punctuation: bool = !matches!(previous, Token::Keyword(_))
+            && matches!(
+                next,
+                None | Some(Token::Punctuation('.' | ',' | ')' | ']' | ';') | Token::Operator(_))
+            );
+        (after_name_position && !phrase_goes_on) || before_punctuation
+    }
+
+    // Language named by the `LANGUAGE` clause of a function or `DO` block,
+    // without any quotes
+    fn body_language(tokens: &[SpannedToken]) -> Option<String> {
+        tokens
+            .windows(2)
+            .find_map(|pair| match (&pair[0].token, &pair[1].token) {
+                (Token::Keyword(word), Token::Keyword(language) | Token::Identifier(language))
+                    if word.eq_ignore_ascii_case("LANGUAGE") =>
+                {
+                    Some(language.clone())
+                }
+                (Token::Keyword(word), Token::Literal(language))
+                    if word.eq_ignore_ascii_case("LANGUAGE") =>
+                {
+                    Some(language.trim_matches('\'').to_string())
+                }
+                _ => None,
+            })
+    }
+
+    // Lays out a dollar quoted string. The body of a `LANGUAGE sql` function
+    // is formatted as SQL of its own when the options ask for it, one level
+    // deeper than its delimiters, which keep their original tag. Text inside
+    // its literals and comments stays as written, and a body that cannot be
+    // tokenized is kept as a whole.
+    fn dollar_quoted(&self, quoted: &DollarQuoted, sql_body: bool) -> Doc {
+        if !(sql_body && self.options.format_function_bodies) || quoted.body.trim().is_empty() {
+            return Doc::Text(quoted.text());
+        }
+        let Ok(tokens) = tokenize(&quoted.body, self.options.dialect.dialect()) else {
+            return Doc::Text(quoted.text());
+        };
+
+        let body: Doc = Formatter::new(tokens, self.options.clone()).document();
+        let delimiter: String = format!("${}$", quoted.tag);
+        Doc::Concat(vec![
+            Doc::text(delimiter.as_str()),
+            Doc::indent(Doc::Concat(vec![Doc::HardLine, body])),
+            Doc::HardLine,
+            Doc::Text(delimiter),
+        ])
+    }
+
+    // A keyword of a raw statement in the keyword case, spaced from the token
+    // before it
+    fn keyword_with_space(&self, keyword: &str, token: &Token, last_token: Option<&Token>) -> Doc {
+        let keyword: String = self.options.keyword_case.apply(keyword);
+        if last_token.is_some_and(|last| self.needs_space(last, token)) {
+            Doc::Text(format!(" {}", keyword))
+        } else {
+            Doc::Text(keyword)
+        }
+    }
+
+    fn token_with_space(&self, token: &Token, last_token: Option<&Token>) -> Doc {
+        let text: String = self.token_text(token);
+        if last_token.is_some_and(|last| self.needs_space(last, token)) {
+            Doc::Text(format!(" {}", text))
+        } else {
+            Doc::Text(text)
+        }
+    }
+}
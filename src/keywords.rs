@@ -0,0 +1,680 @@
+use std::cmp::Ordering;
+
+/// Whether a keyword can also be used as a name.
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[non_exhaustive]
+pub enum Reservation {
+    /// Never an identifier or an implicit alias unless quoted.
+    Reserved,
+    /// A keyword in some positions and a plain name in others.
+    NonReserved,
+}
+
+use Reservation::{NonReserved, Reserved};
+
+/// The words of a SQL dialect that are read as keywords, made up of sorted
+/// tables so a word is found by binary search. Later tables take precedence,
+/// so a dialect can add to the shared table or change how reserved a word is.
+///
+/// Data type and function names are not keywords here; they follow their own
+/// case options and are recognised by the parser where they occur.
+#[derive(Debug)]
+pub struct KeywordSet {
+    tables: &'static [&'static [(&'static str, Reservation)]],
+}
+
+impl KeywordSet {
+    /// How reserved `word` is, in any case, or `None` if it is not a keyword.
+    pub fn lookup(&self, word: &str) -> Option<Reservation> {
+        self.tables.iter().rev().find_map(|table| {
+            table
+                .binary_search_by(|(keyword, _)| compare_ignore_case(keyword, word))
+                .ok()
+                .map(|index| table[index].1)
+        })
+    }
+
+    /// Whether `word`, in any case, is a keyword.
+    pub fn is_keyword(&self, word: &str) -> bool {
+        self.lookup(word).is_some()
+    }
+
+    /// Whether `word`, in any case, is a reserved keyword.
+    pub fn is_reserved(&self, word: &str) -> bool {
+        self.lookup(word) == Some(Reserved)
+    }
+}
+
+// Compares an upper case keyword with a word in any case, without allocating
+fn compare_ignore_case(keyword: &str, word: &str) -> Ordering {
+    keyword
+        .bytes()
+        .cmp(word.bytes().map(|byte| byte.to_ascii_uppercase()))
+}
+
+// Keywords of standard SQL and those shared by the major dialects
+pub static STANDARD: KeywordSet = KeywordSet {
+    tables: &[COMMON_KEYWORDS],
+};
+
+pub static POSTGRES: KeywordSet = KeywordSet {
+    tables: &[COMMON_KEYWORDS, POSTGRES_KEYWORDS],
+};
+
+pub static MYSQL: KeywordSet = KeywordSet {
+    tables: &[COMMON_KEYWORDS, MYSQL_KEYWORDS],
+};
+
+pub static SQL_SERVER: KeywordSet = KeywordSet {
+    tables: &[COMMON_KEYWORDS, SQL_SERVER_KEYWORDS],
+};
+
+pub static SQLITE: KeywordSet = KeywordSet {
+    tables: &[COMMON_KEYWORDS, SQLITE_KEYWORDS],
+};
+
+pub static BIGQUERY: KeywordSet = KeywordSet {
+    tables: &[COMMON_KEYWORDS, BIGQUERY_KEYWORDS],
+};
+
+pub static SNOWFLAKE: KeywordSet = KeywordSet {
+    tables: &[COMMON_KEYWORDS, SNOWFLAKE_KEYWORDS],
+};
+
+// Sorted by byte value, which `lookup` relies on
+const COMMON_KEYWORDS: &[(&str, Reservation)] = &[
+    ("ABORT", NonReserved),
+    ("ABSOLUTE", NonReserved),
+    ("ACCESS", NonReserved),
+    ("ACTION", NonReserved),
+    ("ADD", NonReserved),
+    ("ADMIN", NonReserved),
+    ("AFTER", NonReserved),
+    ("AGGREGATE", NonReserved),
+    ("ALL", Reserved),
+    ("ALSO", NonReserved),
+    ("ALTER", Reserved),
+    ("ALWAYS", NonReserved),
+    ("ANALYZE", NonReserved),
+    ("AND", Reserved),
+    ("ANY", NonReserved),
+    ("ARRAY", NonReserved),
+    ("AS", Reserved),
+    ("ASC", Reserved),
+    ("ASENSITIVE", NonReserved),
+    ("ASSERTION", NonReserved),
+    ("ASYMMETRIC", NonReserved),
+    ("AT", NonReserved),
+    ("ATOMIC", NonReserved),
+    ("ATTACH", NonReserved),
+    ("ATTRIBUTE", NonReserved),
+    ("AUTHORIZATION", NonReserved),
+    ("AUTO_INCREMENT", NonReserved),
+    ("BACKWARD", NonReserved),
+    ("BEFORE", NonReserved),
+    ("BEGIN", NonReserved),
+    ("BETWEEN", Reserved),
+    ("BOTH", NonReserved),
+    ("BREADTH", NonReserved),
+    ("BY", Reserved),
+    ("CACHE", NonReserved),
+    ("CALL", NonReserved),
+    ("CALLED", NonReserved),
+    ("CASCADE", NonReserved),
+    ("CASCADED", NonReserved),
+    ("CASE", Reserved),
+    ("CAST", NonReserved),
+    ("CATALOG", NonReserved),
+    ("CHAIN", NonReserved),
+    ("CHARACTERISTICS", NonReserved),
+    ("CHECK", Reserved),
+    ("CHECKPOINT", NonReserved),
+    ("CLOSE", NonReserved),
+    ("CLUSTER", NonReserved),
+    ("COLLATE", NonReserved),
+    ("COLLATION", NonReserved),
+    ("COLUMN", NonReserved),
+    ("COLUMNS", NonReserved),
+    ("COMMENT", NonReserved),
+    ("COMMENTS", NonReserved),
+    ("COMMIT", NonReserved),
+    ("COMMITTED", NonReserved),
+    ("CONCURRENTLY", NonReserved),
+    ("CONFLICT", NonReserved),
+    ("CONNECT", NonReserved),
+    ("CONNECTION", NonReserved),
+    ("CONSTRAINT", Reserved),
+    ("CONSTRAINTS", NonReserved),
+    ("CONTINUE", NonReserved),
+    ("COPY", NonReserved),
+    ("COST", NonReserved),
+    ("CREATE", Reserved),
+    ("CROSS", Reserved),
+    ("CUBE", NonReserved),
+    ("CURRENT", NonReserved),
+    ("CURRENT_DATE", Reserved),
+    ("CURRENT_TIME", Reserved),
+    ("CURRENT_TIMESTAMP", Reserved),
+    ("CURRENT_USER", Reserved),
+    ("CURSOR", NonReserved),
+    ("CYCLE", NonReserved),
+    ("DATA", NonReserved),
+    ("DATABASE", NonReserved),
+    ("DAY", NonReserved),
+    ("DEALLOCATE", NonReserved),
+    ("DECLARE", NonReserved),
+    ("DEFAULT", Reserved),
+    ("DEFAULTS", NonReserved),
+    ("DEFERRABLE", NonReserved),
+    ("DEFERRED", NonReserved),
+    ("DEFINER", NonReserved),
+    ("DELETE", Reserved),
+    ("DELIMITER", NonReserved),
+    ("DEPTH", NonReserved),
+    ("DESC", Reserved),
+    ("DESCRIBE", NonReserved),
+    ("DETACH", NonReserved),
+    ("DETERMINISTIC", NonReserved),
+    ("DISABLE", NonReserved),
+    ("DISCARD", NonReserved),
+    ("DISTINCT", Reserved),
+    ("DO", NonReserved),
+    ("DOMAIN", NonReserved),
+    ("DROP", Reserved),
+    ("EACH", NonReserved),
+    ("ELSE", Reserved),
+    ("ELSEIF", NonReserved),
+    ("ELSIF", NonReserved),
+    ("ENABLE", NonReserved),
+    ("ENCODING", NonReserved),
+    ("ENCRYPTED", NonReserved),
+    ("END", Reserved),
+    ("ENUM", NonReserved),
+    ("ESCAPE", NonReserved),
+    ("EVENT", NonReserved),
+    ("EXCEPT", Reserved),
+    ("EXCLUDE", NonReserved),
+    ("EXCLUDING", NonReserved),
+    ("EXCLUSIVE", NonReserved),
+    ("EXECUTE", NonReserved),
+    ("EXISTS", Reserved),
+    ("EXIT", NonReserved),
+    ("EXPLAIN", NonReserved),
+    ("EXPRESSION", NonReserved),
+    ("EXTENSION", NonReserved),
+    ("EXTERNAL", NonReserved),
+    ("FALSE", Reserved),
+    ("FETCH", Reserved),
+    ("FILTER", NonReserved),
+    ("FIRST", NonReserved),
+    ("FOLLOWING", NonReserved),
+    ("FOR", Reserved),
+    ("FORCE", NonReserved),
+    ("FOREIGN", Reserved),
+    ("FORWARD", NonReserved),
+    ("FROM", Reserved),
+    ("FULL", Reserved),
+    ("FUNCTION", NonReserved),
+    ("FUNCTIONS", NonReserved),
+    ("GENERATED", NonReserved),
+    ("GLOBAL", NonReserved),
+    ("GRANT", Reserved),
+    ("GRANTED", NonReserved),
+    ("GROUP", Reserved),
+    ("GROUPING", NonReserved),
+    ("GROUPS", NonReserved),
+    ("HANDLER", NonReserved),
+    ("HAVING", Reserved),
+    ("HOLD", NonReserved),
+    ("HOUR", NonReserved),
+    ("IDENTITY", NonReserved),
+    ("IF", NonReserved),
+    ("ILIKE", Reserved),
+    ("IMMEDIATE", NonReserved),
+    ("IMMUTABLE", NonReserved),
+    ("IMPLICIT", NonReserved),
+    ("IN", Reserved),
+    ("INCLUDE", NonReserved),
+    ("INCLUDING", NonReserved),
+    ("INCREMENT", NonReserved),
+    ("INDEX", NonReserved),
+    ("INDEXES", NonReserved),
+    ("INHERIT", NonReserved),
+    ("INHERITS", NonReserved),
+    ("INITIALLY", NonReserved),
+    ("INLINE", NonReserved),
+    ("INNER", Reserved),
+    ("INOUT", NonReserved),
+    ("INPUT", NonReserved),
+    ("INSENSITIVE", NonReserved),
+    ("INSERT", Reserved),
+    ("INSTEAD", NonReserved),
+    ("INTERSECT", Reserved),
+    ("INTO", Reserved),
+    ("INVOKER", NonReserved),
+    ("IS", Reserved),
+    ("ISOLATION", NonReserved),
+    ("ITERATE", NonReserved),
+    ("JOIN", Reserved),
+    ("KEY", NonReserved),
+    ("LANGUAGE", NonReserved),
+    ("LARGE", NonReserved),
+    ("LAST", NonReserved),
+    ("LATERAL", Reserved),
+    ("LEADING", NonReserved),
+    ("LEAKPROOF", NonReserved),
+    ("LEAVE", NonReserved),
+    ("LEFT", Reserved),
+    ("LEVEL", NonReserved),
+    ("LIKE", Reserved),
+    ("LIMIT", Reserved),
+    ("LISTEN", NonReserved),
+    ("LOAD", NonReserved),
+    ("LOCAL", NonReserved),
+    ("LOCALTIME", Reserved),
+    ("LOCALTIMESTAMP", Reserved),
+    ("LOCATION", NonReserved),
+    ("LOCK", NonReserved),
+    ("LOCKED", NonReserved),
+    ("LOGGED", NonReserved),
+    ("LOOP", NonReserved),
+    ("MATCH", NonReserved),
+    ("MATCHED", NonReserved),
+    ("MATERIALIZED", NonReserved),
+    ("MAXVALUE", NonReserved),
+    ("MERGE", NonReserved),
+    ("METHOD", NonReserved),
+    ("MINUS", Reserved),
+    ("MINUTE", NonReserved),
+    ("MINVALUE", NonReserved),
+    ("MODE", NonReserved),
+    ("MONTH", NonReserved),
+    ("MOVE", NonReserved),
+    ("NAMES", NonReserved),
+    ("NATURAL", Reserved),
+    ("NEW", NonReserved),
+    ("NEXT", NonReserved),
+    ("NFC", NonReserved),
+    ("NO", NonReserved),
+    ("NONE", NonReserved),
+    ("NOT", Reserved),
+    ("NOTHING", NonReserved),
+    ("NOTIFY", NonReserved),
+    ("NOWAIT", NonReserved),
+    ("NULL", Reserved),
+    ("NULLS", NonReserved),
+    ("OBJECT", NonReserved),
+    ("OF", NonReserved),
+    ("OFF", NonReserved),
+    ("OFFSET", Reserved),
+    ("OIDS", NonReserved),
+    ("OLD", NonReserved),
+    ("ON", Reserved),
+    ("ONLY", NonReserved),
+    ("OPERATOR", NonReserved),
+    ("OPTION", NonReserved),
+    ("OPTIONS", NonReserved),
+    ("OR", Reserved),
+    ("ORDER", Reserved),
+    ("ORDINALITY", NonReserved),
+    ("OTHERS", NonReserved),
+    ("OUT", NonReserved),
+    ("OUTER", Reserved),
+    ("OVER", NonReserved),
+    ("OVERLAPS", NonReserved),
+    ("OVERRIDING", NonReserved),
+    ("OWNED", NonReserved),
+    ("OWNER", NonReserved),
+    ("PARALLEL", NonReserved),
+    ("PARSER", NonReserved),
+    ("PARTIAL", NonReserved),
+    ("PARTITION", NonReserved),
+    ("PASSING", NonReserved),
+    ("PASSWORD", NonReserved),
+    ("PERCENT", NonReserved),
+    ("PLACING", NonReserved),
+    ("PLANS", NonReserved),
+    ("POLICY", NonReserved),
+    ("PRECEDING", NonReserved),
+    ("PREPARE", NonReserved),
+    ("PREPARED", NonReserved),
+    ("PRESERVE", NonReserved),
+    ("PRIMARY", Reserved),
+    ("PRIOR", NonReserved),
+    ("PRIVILEGES", NonReserved),
+    ("PROCEDURAL", NonReserved),
+    ("PROCEDURE", NonReserved),
+    ("PROCEDURES", NonReserved),
+    ("PROGRAM", NonReserved),
+    ("PUBLICATION", NonReserved),
+    ("QUOTE", NonReserved),
+    ("RANGE", NonReserved),
+    ("READ", NonReserved),
+    ("REASSIGN", NonReserved),
+    ("RECURSIVE", NonReserved),
+    ("REF", NonReserved),
+    ("REFERENCES", Reserved),
+    ("REFERENCING", NonReserved),
+    ("REFRESH", NonReserved),
+    ("REINDEX", NonReserved),
+    ("RELATIVE", NonReserved),
+    ("RELEASE", NonReserved),
+    ("RENAME", NonReserved),
+    ("REPEAT", NonReserved),
+    ("REPEATABLE", NonReserved),
+    ("REPLACE", NonReserved),
+    ("REPLICA", NonReserved),
+    ("RESET", NonReserved),
+    ("RESTART", NonReserved),
+    ("RESTRICT", NonReserved),
+    ("RETURN", NonReserved),
+    ("RETURNING", Reserved),
+    ("RETURNS", NonReserved),
+    ("REVOKE", Reserved),
+    ("RIGHT", Reserved),
+    ("ROLE", NonReserved),
+    ("ROLLBACK", NonReserved),
+    ("ROLLUP", NonReserved),
+    ("ROUTINE", NonReserved),
+    ("ROUTINES", NonReserved),
+    ("ROW", NonReserved),
+    ("ROWS", NonReserved),
+    ("RULE", NonReserved),
+    ("SAVEPOINT", NonReserved),
+    ("SCHEMA", NonReserved),
+    ("SCHEMAS", NonReserved),
+    ("SCROLL", NonReserved),
+    ("SEARCH", NonReserved),
+    ("SECOND", NonReserved),
+    ("SECURITY", NonReserved),
+    ("SELECT", Reserved),
+    ("SEQUENCE", NonReserved),
+    ("SEQUENCES", NonReserved),
+    ("SERIALIZABLE", NonReserved),
+    ("SERVER", NonReserved),
+    ("SESSION", NonReserved),
+    ("SESSION_USER", Reserved),
+    ("SET", Reserved),
+    ("SETS", NonReserved),
+    ("SHARE", NonReserved),
+    ("SHOW", NonReserved),
+    ("SIMILAR", NonReserved),
+    ("SIMPLE", NonReserved),
+    ("SKIP", NonReserved),
+    ("SNAPSHOT", NonReserved),
+    ("SOME", NonReserved),
+    ("SQL", NonReserved),
+    ("STABLE", NonReserved),
+    ("STANDALONE", NonReserved),
+    ("START", NonReserved),
+    ("STATEMENT", NonReserved),
+    ("STATISTICS", NonReserved),
+    ("STDIN", NonReserved),
+    ("STDOUT", NonReserved),
+    ("STORAGE", NonReserved),
+    ("STORED", NonReserved),
+    ("STRICT", NonReserved),
+    ("STRIP", NonReserved),
+    ("SUBSCRIPTION", NonReserved),
+    ("SUPPORT", NonReserved),
+    ("SYMMETRIC", NonReserved),
+    ("SYSID", NonReserved),
+    ("SYSTEM", NonReserved),
+    ("TABLE", Reserved),
+    ("TABLES", NonReserved),
+    ("TABLESAMPLE", NonReserved),
+    ("TABLESPACE", NonReserved),
+    ("TEMP", NonReserved),
+    ("TEMPLATE", NonReserved),
+    ("TEMPORARY", NonReserved),
+    ("THEN", Reserved),
+    ("TIES", NonReserved),
+    ("TO", Reserved),
+    ("TRAILING", NonReserved),
+    ("TRANSACTION", NonReserved),
+    ("TRANSFORM", NonReserved),
+    ("TRIGGER", NonReserved),
+    ("TRUE", Reserved),
+    ("TRUNCATE", NonReserved),
+    ("TRUSTED", NonReserved),
+    ("TYPE", NonReserved),
+    ("TYPES", NonReserved),
+    ("UNBOUNDED", NonReserved),
+    ("UNCOMMITTED", NonReserved),
+    ("UNENCRYPTED", NonReserved),
+    ("UNION", Reserved),
+    ("UNIQUE", Reserved),
+    ("UNKNOWN", NonReserved),
+    ("UNLISTEN", NonReserved),
+    ("UNLOGGED", NonReserved),
+    ("UNTIL", NonReserved),
+    ("UPDATE", Reserved),
+    ("USER", NonReserved),
+    ("USING", Reserved),
+    ("VACUUM", NonReserved),
+    ("VALID", NonReserved),
+    ("VALIDATE", NonReserved),
+    ("VALIDATOR", NonReserved),
+    ("VALUE", NonReserved),
+    ("VALUES", Reserved),
+    ("VARIADIC", NonReserved),
+    ("VERBOSE", NonReserved),
+    ("VERSION", NonReserved),
+    ("VIEW", NonReserved),
+    ("VIEWS", NonReserved),
+    ("VOLATILE", NonReserved),
+    ("WHEN", Reserved),
+    ("WHERE", Reserved),
+    ("WHILE", NonReserved),
+    ("WHITESPACE", NonReserved),
+    ("WINDOW", Reserved),
+    ("WITH", Reserved),
+    ("WITHIN", NonReserved),
+    ("WITHOUT", NonReserved),
+    ("WORK", NonReserved),
+    ("WRAPPER", NonReserved),
+    ("WRITE", NonReserved),
+    ("YEAR", NonReserved),
+    ("ZONE", NonReserved),
+];
+
+const POSTGRES_KEYWORDS: &[(&str, Reservation)] = &[
+    ("ANALYSE", NonReserved),
+    ("ISNULL", Reserved),
+    ("NOTNULL", Reserved),
+    ("OVERLAY", NonReserved),
+    ("VARIADIC", Reserved),
+];
+
+const MYSQL_KEYWORDS: &[(&str, Reservation)] = &[
+    ("ALGORITHM", NonReserved),
+    ("CHARSET", NonReserved),
+    ("DELAYED", NonReserved),
+    ("DIV", Reserved),
+    ("DUPLICATE", NonReserved),
+    ("ENGINE", NonReserved),
+    ("HIGH_PRIORITY", NonReserved),
+    ("IGNORE", Reserved),
+    ("LOW_PRIORITY", NonReserved),
+    ("REGEXP", Reserved),
+    ("RLIKE", Reserved),
+    ("SEPARATOR", NonReserved),
+    ("SQL_CALC_FOUND_ROWS", NonReserved),
+    ("STRAIGHT_JOIN", Reserved),
+    ("UNSIGNED", NonReserved),
+    ("XOR", Reserved),
+    ("ZEROFILL", NonReserved),
+];
+
+const SQL_SERVER_KEYWORDS: &[(&str, Reservation)] = &[
+    ("APPLY", NonReserved),
+    ("CLUSTERED", NonReserved),
+    ("EXEC", NonReserved),
+    ("NOCOUNT", NonReserved),
+    ("NOLOCK", NonReserved),
+    ("NONCLUSTERED", NonReserved),
+    ("OUTPUT", NonReserved),
+    ("PIVOT", Reserved),
+    ("PROC", NonReserved),
+    ("TOP", Reserved),
+    ("TRAN", NonReserved),
+    ("UNPIVOT", Reserved),
+];
+
+const SQLITE_KEYWORDS: &[(&str, Reservation)] = &[
+    ("AUTOINCREMENT", NonReserved),
+    ("FAIL", NonReserved),
+    ("GLOB", Reserved),
+    ("IGNORE", NonReserved),
+    ("INDEXED", NonReserved),
+    ("ISNULL", Reserved),
+    ("NOTNULL", Reserved),
+    ("PRAGMA", NonReserved),
+    ("REGEXP", Reserved),
+    ("VIRTUAL", NonReserved),
+];
+
+const BIGQUERY_KEYWORDS: &[(&str, Reservation)] = &[
+    ("ASSERT", NonReserved),
+    ("PIVOT", NonReserved),
+    ("QUALIFY", Reserved),
+    ("UNNEST", NonReserved),
+    ("UNPIVOT", NonReserved),
+];
+
+const SNOWFLAKE_KEYWORDS: &[(&str, Reservation)] = &[
+    ("CLONE", NonReserved),
+    ("MATCH_RECOGNIZE", NonReserved),
+    ("PIVOT", NonReserved),
+    ("QUALIFY", Reserved),
+    ("SAMPLE", NonReserved),
+    ("STAGE", NonReserved),
+    ("TRANSIENT", NonReserved),
+    ("UNPIVOT", NonReserved),
+    ("WAREHOUSE", NonReserved),
+];
+
+// Keyword sequences that read as a single keyword, e.g. `GROUP BY`
+const COMPOUND_KEYWORDS: &[&[&str]] = &[
+    &["CREATE", "OR", "REPLACE"],
+    &["CROSS", "APPLY"],
+    &["CROSS", "JOIN"],
+    &["DELETE", "FROM"],
+    &["DO", "NOTHING"],
+    &["DO", "UPDATE"],
+    &["EXCEPT", "ALL"],
+    &["FOR", "SHARE"],
+    &["FOR", "UPDATE"],
+    &["FOREIGN", "KEY"],
+    &["FULL", "JOIN"],
+    &["FULL", "OUTER", "JOIN"],
+    &["GROUP", "BY"],
+    &["IF", "EXISTS"],
+    &["IF", "NOT", "EXISTS"],
+    &["INNER", "JOIN"],
+    &["INSERT", "INTO"],
+    &["INTERSECT", "ALL"],
+    &["IS", "DISTINCT", "FROM"],
+    &["IS", "NOT"],
+    &["IS", "NOT", "DISTINCT", "FROM"],
+    &["LEFT", "JOIN"],
+    &["LEFT", "OUTER", "JOIN"],
+    &["NATURAL", "JOIN"],
+    &["NOT", "BETWEEN"],
+    &["NOT", "ILIKE"],
+    &["NOT", "IN"],
+    &["NOT", "LIKE"],
+    &["NULLS", "FIRST"],
+    &["NULLS", "LAST"],
+    &["ON", "CONFLICT"],
+    &["ORDER", "BY"],
+    &["OUTER", "APPLY"],
+    &["PARTITION", "BY"],
+    &["PRIMARY", "KEY"],
+    &["RIGHT", "JOIN"],
+    &["RIGHT", "OUTER", "JOIN"],
+    &["UNION", "ALL"],
+    &["WITHIN", "GROUP"],
+];
+
+// Number of words in the longest compound keyword starting with the word at
+// offset 0 of `word_at`, which gives the words that follow (None past the
+// last one or at anything but a word)
+pub fn compound_len<'a>(word_at: impl Fn(usize) -> Option<&'a str>) -> Option<usize> {
+    COMPOUND_KEYWORDS
+        .iter()
+        .filter(|compound| {
+            compound.iter().enumerate().all(|(n, keyword)| {
+                word_at(n).is_some_and(|word| word.eq_ignore_ascii_case(keyword))
+            })
+        })
+        .map(|compound| compound.len())
+        .max()
+}
+
+// Names of the built-in data types of the major dialects, sorted by byte
+// value. Like the keyword tables, they are looked up by binary search.
+const DATA_TYPES: &[&str] = &[
+    "BIGINT",
+    "BIGSERIAL",
+    "BINARY",
+    "BIT",
+    "BLOB",
+    "BOOL",
+    "BOOLEAN",
+    "BYTEA",
+    "CHAR",
+    "CHARACTER",
+    "CLOB",
+    "DATE",
+    "DATETIME",
+    "DATETIME2",
+    "DATETIMEOFFSET",
+    "DECIMAL",
+    "DOUBLE",
+    "FLOAT",
+    "FLOAT4",
+    "FLOAT8",
+    "INT",
+    "INT2",
+    "INT4",
+    "INT64",
+    "INT8",
+    "INTEGER",
+    "JSON",
+    "JSONB",
+    "LONGTEXT",
+    "MEDIUMINT",
+    "MEDIUMTEXT",
+    "MONEY",
+    "NCHAR",
+    "NUMBER",
+    "NUMERIC",
+    "NVARCHAR",
+    "REAL",
+    "SERIAL",
+    "SMALLINT",
+    "SMALLSERIAL",
+    "STRING",
+    "TEXT",
+    "TIME",
+    "TIMESTAMP",
+    "TIMESTAMPTZ",
+    "TIMESTAMP_LTZ",
+    "TIMESTAMP_NTZ",
+    "TIMESTAMP_TZ",
+    "TINYINT",
+    "TINYTEXT",
+    "UUID",
+    "VARBINARY",
+    "VARCHAR",
+    "VARIANT",
+    "XML",
+];
+
+// Whether `word`, in any case, names a built-in data type
+pub fn is_data_type(word: &str) -> bool {
+    DATA_TYPES
+        .binary_search_by(|name| compare_ignore_case(name, word))
+        .is_ok()
+}
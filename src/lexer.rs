@@ -0,0 +1,953 @@
+use std::fmt;
+
+use crate::dialect::{Dialect, ParameterStyle};
+
+/// Enum to represent different SQL tokens.
+#[derive(Debug, PartialEq, Clone)]
+#[non_exhaustive]
+pub enum Token {
+    /// Keyword of the dialect, as written.
+    Keyword(String),
+    /// Unquoted name that is not a keyword.
+    Identifier(String),
+    /// Identifier wrapped in "", `` or [], kept verbatim including its delimiters.
+    QuotedIdentifier(String),
+    /// String or number literal, kept verbatim.
+    Literal(String),
+    /// Bind parameter placeholder, e.g. `?`, `$1`, `:name` or `%(name)s`.
+    Parameter(String),
+    /// Operator of the dialect, e.g. `<>` or `::`.
+    Operator(String),
+    /// One of `( ) , ; .`, or `[ ]` where they do not quote identifiers.
+    Punctuation(char),
+    /// Line or block comment.
+    Comment(Comment),
+    /// String quoted between `$$` or `$tag$` delimiters, e.g. a function body.
+    DollarQuoted(DollarQuoted),
+    /// Reference to a stage of files, e.g. `@my_stage/path/`, `@~` or
+    /// `@%table`, kept verbatim.
+    Stage(String),
+    /// Source text that could not be tokenized, kept verbatim up to the end of
+    /// its line, e.g. a string literal missing its closing quote.
+    Invalid(String),
+    /// Run of spaces, tabs and line breaks.
+    Whitespace,
+    /// End of the input.
+    Eof,
+}
+
+/// Whether a comment runs to the end of the line or is delimited by /* */.
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[non_exhaustive]
+pub enum CommentKind {
+    /// `--` comment, or `#` where the dialect has them.
+    Line,
+    /// `/* */` comment.
+    Block,
+}
+
+/// A comment, with its text kept verbatim including the delimiters.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Comment {
+    /// Line or block comment.
+    pub kind: CommentKind,
+    /// The comment as written.
+    pub text: String,
+}
+
+/// A dollar quoted string, split into the tag of its delimiters (empty for
+/// `$$`) and the body between them, taken verbatim.
+#[derive(Debug, PartialEq, Clone)]
+pub struct DollarQuoted {
+    /// Tag between the dollar signs of the delimiters.
+    pub tag: String,
+    /// Text between the delimiters.
+    pub body: String,
+}
+
+impl DollarQuoted {
+    /// The string as written, delimiters included.
+    pub fn text(&self) -> String {
+        format!("${0}${1}${0}$", self.tag, self.body)
+    }
+}
+
+/// A position in the source text; line and column are 1-based, column counts characters.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Location {
+    /// Byte offset from the start of the source text.
+    pub offset: usize,
+    /// Line number, starting at 1.
+    pub line: usize,
+    /// Character column within the line, starting at 1.
+    pub column: usize,
+}
+
+/// The source range a token was read from, `end` being exclusive.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Span {
+    /// Where the range starts.
+    pub start: Location,
+    /// Where the range ends, exclusive.
+    pub end: Location,
+}
+
+/// A token together with where it came from in the source.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SpannedToken {
+    /// The token read.
+    pub token: Token,
+    /// Source range of the token.
+    pub span: Span,
+}
+
+/// Error raised when the input cannot be tokenized, spanning the offending token.
+#[derive(Debug, PartialEq, Clone)]
+pub struct LexError {
+    /// What went wrong, e.g. `unterminated string literal`.
+    pub message: String,
+    /// Source range of the offending text.
+    pub span: Span,
+}
+
+impl fmt::Display for LexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at line {}, column {}",
+            self.message, self.span.start.line, self.span.start.column
+        )
+    }
+}
+
+impl std::error::Error for LexError {}
+
+/// Lexer struct to handle main tokenization.
+pub struct Lexer {
+    input: Vec<char>,
+    position: usize,
+    // Index where the token being read starts, and its source location
+    token_start: usize,
+    location: Location,
+    // Quoting, operators, keywords and comments of the SQL being lexed
+    dialect: &'static dyn Dialect,
+}
+
+impl Lexer {
+    /// Create a new Lexer instance.
+    pub fn new(input: &str, dialect: &'static dyn Dialect) -> Self {
+        Lexer {
+            input: input.chars().collect(),
+            position: 0,
+            token_start: 0,
+            location: Location {
+                offset: 0,
+                line: 1,
+                column: 1,
+            },
+            dialect,
+        }
+    }
+
+    // Computes the location of `position` by reading forward from the
+    // start of the current token
+    fn location_at(&self, position: usize) -> Location {
+        let mut location: Location = self.location;
+
+        for &ch in &self.input[self.token_start..position] {
+            location.offset += ch.len_utf8();
+            if ch == '\n' {
+                location.line += 1;
+                location.column = 1;
+            } else {
+                location.column += 1;
+            }
+        }
+        location
+    }
+
+    // Builds an error spanning the current token up to the current position
+    fn error(&self, message: &str) -> LexError {
+        LexError {
+            message: message.to_string(),
+            span: Span {
+                start: self.location,
+                end: self.location_at(self.position),
+            },
+        }
+    }
+
+    // Looks at the character `offset` places past the current position
+    fn peek(&self, offset: usize) -> Option<char> {
+        self.input.get(self.position + offset).copied()
+    }
+
+    /// Reads the next token and records the span of source it covers.
+    pub fn next_token(&mut self) -> Result<SpannedToken, LexError> {
+        let token: Token = self.read_token()?;
+        let end: Location = self.location_at(self.position);
+        let span: Span = Span {
+            start: self.location,
+            end,
+        };
+
+        self.token_start = self.position;
+        self.location = end;
+        Ok(SpannedToken { token, span })
+    }
+
+    // Recovers from an error in the token being read by taking the rest of
+    // its line as an invalid token, so that lexing resumes on the next line.
+    // Returns that token and the error, narrowed to its span.
+    fn recover(&mut self, mut err: LexError) -> (SpannedToken, LexError) {
+        self.position = self.input[self.token_start..]
+            .iter()
+            .position(|&ch| ch == '\n')
+            .map_or(self.input.len(), |len| self.token_start + len);
+
+        let text: String = self.input[self.token_start..self.position]
+            .iter()
+            .collect::<String>()
+            .trim_end()
+            .to_string();
+        let span: Span = Span {
+            start: self.location,
+            end: self.location_at(self.token_start + text.chars().count()),
+        };
+
+        self.location = self.location_at(self.position);
+        self.token_start = self.position;
+        err.span = span;
+        (
+            SpannedToken {
+                token: Token::Invalid(text),
+                span,
+            },
+            err,
+        )
+    }
+
+    // Gets next character from input string
+    fn read_token(&mut self) -> Result<Token, LexError> {
+        if self.position >= self.input.len() {
+            return Ok(Token::Eof);
+        }
+
+        let ch: char = self.input[self.position];
+        self.position += 1;
+
+        let token: Token = match ch {
+            ' ' | '\t' | '\r' | '\n' => Token::Whitespace,
+            '-' if self.peek(0) == Some('-') => self.read_line_comment(),
+            '#' if self.dialect.hash_comments() => self.read_line_comment(),
+            '/' if self.peek(0) == Some('*') => self.read_block_comment()?,
+            '.' if self.peek(0).is_some_and(|next| next.is_ascii_digit()) => {
+                self.read_number_literal(ch)
+            }
+            ',' | ';' | '(' | ')' | '.' => Token::Punctuation(ch),
+            _ if self.dialect.string_quotes().contains(&ch) => {
+                self.read_string_literal(&ch.to_string(), ch, self.dialect.backslash_escapes())?
+            }
+            'E' | 'e' if self.peek(0) == Some('\'') && self.dialect.escape_strings() => {
+                self.position += 1;
+                self.read_string_literal(&format!("{ch}'"), '\'', true)?
+            }
+            // National character strings
+            'N' | 'n' if self.peek(0) == Some('\'') => {
+                self.position += 1;
+                self.read_string_literal(&format!("{ch}'"), '\'', self.dialect.backslash_escapes())?
+            }
+            'X' | 'x' | 'B' | 'b' if self.peek(0) == Some('\'') => {
+                self.position += 1;
+                self.read_string_literal(&format!("{ch}'"), '\'', false)?
+            }
+            '$' if self.dialect.dollar_quoted_strings() && self.dollar_tag_len().is_some() => {
+                self.read_dollar_quoted_string()?
+            }
+            '@' if self.dialect.stage_references()
+                && let Some(len) = self.stage_len() =>
+            {
+                let start: usize = self.position - 1;
+                self.position += len;
+                Token::Stage(self.input[start..self.position].iter().collect())
+            }
+            _ if let Some(len) = self.parameter_len(ch) => {
+                let start: usize = self.position - 1;
+                self.position += len;
+                Token::Parameter(self.input[start..self.position].iter().collect())
+            }
+            '[' if self.dialect.bracket_subscripts() && self.follows_operand() => {
+                Token::Punctuation(ch)
+            }
+            _ if let Some(&(_, close)) = self
+                .dialect
+                .identifier_quotes()
+                .iter()
+                .find(|(open, _)| *open == ch) =>
+            {
+                self.read_quoted_identifier(ch, close)?
+            }
+            '[' | ']' => Token::Punctuation(ch),
+            _ if self.dialect.is_identifier_start(ch) => self.read_identifier(ch),
+            _ if ch.is_ascii_digit() => self.read_number_literal(ch),
+            _ if let Some(operator) = self.operator_at(self.position - 1) => {
+                self.position += operator.chars().count() - 1;
+                Token::Operator(operator.to_string())
+            }
+            _ => Token::Identifier(ch.to_string()),
+        };
+
+        Ok(token)
+    }
+
+    // Helper function to read a `--` (or `#`) comment up to (but not including)
+    // the line break
+    fn read_line_comment(&mut self) -> Token {
+        let mut text: String = self.input[self.position - 1].to_string();
+
+        while self.position < self.input.len() && self.input[self.position] != '\n' {
+            text.push(self.input[self.position]);
+            self.position += 1;
+        }
+
+        Token::Comment(Comment {
+            kind: CommentKind::Line,
+            text: text.trim_end().to_string(),
+        })
+    }
+
+    // Helper function to read a `/* */` comment, with nested block comments in
+    // the dialects that allow them
+    fn read_block_comment(&mut self) -> Result<Token, LexError> {
+        let mut text: String = String::from("/*");
+        let mut depth: usize = 1;
+        self.position += 1;
+
+        while self.position < self.input.len() && depth > 0 {
+            let ch: char = self.input[self.position];
+            if ch == '/' && self.peek(1) == Some('*') && self.dialect.nested_block_comments() {
+                depth += 1;
+                text.push_str("/*");
+                self.position += 2;
+            } else if ch == '*' && self.peek(1) == Some('/') {
+                depth -= 1;
+                text.push_str("*/");
+                self.position += 2;
+            } else {
+                text.push(ch);
+                self.position += 1;
+            }
+        }
+
+        if depth > 0 {
+            return Err(self.error("unterminated block comment"));
+        }
+        Ok(Token::Comment(Comment {
+            kind: CommentKind::Block,
+            text,
+        }))
+    }
+
+    // Helper function to read a complete idetifier or keyword
+    fn read_identifier(&mut self, first_char: char) -> Token {
+        let mut ident: String = String::new();
+        ident.push(first_char);
+
+        while self.position < self.input.len()
+            && self.dialect.is_identifier_part(self.input[self.position])
+        {
+            ident.push(self.input[self.position]);
+            self.position += 1;
+        }
+
+        if self.dialect.keywords().is_keyword(&ident) {
+            Token::Keyword(ident)
+        } else {
+            Token::Identifier(ident)
+        }
+    }
+
+    // Finds the longest operator in the operator table that starts at `start`.
+    // A character that only begins longer operators (e.g. a lone `:` where
+    // just `:=` exists) is no operator.
+    fn operator_at(&self, start: usize) -> Option<&'static str> {
+        self.dialect.operators().iter().copied().find(|op| {
+            op.chars()
+                .enumerate()
+                .all(|(i, ch)| self.input.get(start + i) == Some(&ch))
+        })
+    }
+
+    // Helper function to read a delimited identifier. A doubled closing
+    // delimiter (e.g. `""` or `]]`) is an escaped delimiter, not the end.
+    fn read_quoted_identifier(&mut self, open: char, close: char) -> Result<Token, LexError> {
+        let mut ident: String = String::new();
+        ident.push(open);
+
+        loop {
+            let Some(ch) = self.peek(0) else {
+                return Err(self.error("unterminated quoted identifier"));
+            };
+            ident.push(ch);
+            self.position += 1;
+
+            if ch == close {
+                if self.peek(0) == Some(close) {
+                    ident.push(close);
+                    self.position += 1;
+                } else {
+                    return Ok(Token::QuotedIdentifier(ident));
+                }
+            }
+        }
+    }
+
+    // Helper function to read a string literal whose opening `prefix` (the
+    // `quote` plus any `E` marker) has already been consumed. A doubled quote
+    // is an escaped quote; with `backslash_escapes` a backslash escapes any character.
+    fn read_string_literal(
+        &mut self,
+        prefix: &str,
+        quote: char,
+        backslash_escapes: bool,
+    ) -> Result<Token, LexError> {
+        let mut literal: String = String::from(prefix);
+
+        loop {
+            let Some(ch) = self.peek(0) else {
+                return Err(self.error("unterminated string literal"));
+            };
+            literal.push(ch);
+            self.position += 1;
+
+            match ch {
+                '\\' if backslash_escapes => {
+                    if let Some(escaped) = self.peek(0) {
+                        literal.push(escaped);
+                        self.position += 1;
+                    }
+                }
+                _ if ch == quote && self.peek(0) == Some(quote) => {
+                    literal.push(quote);
+                    self.position += 1;
+                }
+                _ if ch == quote => return Ok(Token::Literal(literal)),
+                _ => {}
+            }
+        }
+    }
+
+    // Length of the `tag$` that follows a `$`, if the `$` opens a dollar
+    // quoted string such as `$$...$$` or `$body$...$body$`
+    fn dollar_tag_len(&self) -> Option<usize> {
+        let mut len: usize = 0;
+
+        while let Some(ch) = self.peek(len) {
+            let valid: bool = if len == 0 {
+                ch.is_alphabetic() || ch == '_'
+            } else {
+                ch.is_alphanumeric() || ch == '_'
+            };
+            if !valid {
+                break;
+            }
+            len += 1;
+        }
+
+        (self.peek(len) == Some('$')).then_some(len + 1)
+    }
+
+    // Number of characters past the current position that belong to a bind
+    // parameter opened by `ch`, the character just consumed, if `ch` opens one
+    // in a style of the dialect
+    fn parameter_len(&self, ch: char) -> Option<usize> {
+        let styles: &[ParameterStyle] = self.dialect.parameter_styles();
+        let word_len = |offset: usize| -> usize {
+            (offset..)
+                .take_while(|&i| {
+                    self.peek(i)
+                        .is_some_and(|ch| ch.is_alphanumeric() || ch == '_')
+                })
+                .count()
+        };
+        let name_len = |offset: usize| -> Option<usize> {
+            let starts_name: bool = self
+                .peek(offset)
+                .is_some_and(|ch| ch.is_alphabetic() || ch == '_');
+            starts_name.then(|| word_len(offset))
+        };
+
+        match ch {
+            '?' if styles.contains(&ParameterStyle::QuestionMark) => Some(
+                (0..)
+                    .take_while(|&i| self.peek(i).is_some_and(|ch| ch.is_ascii_digit()))
+                    .count(),
+            ),
+            '$' if styles.contains(&ParameterStyle::Dollar) => {
+                Some(word_len(0)).filter(|&len| len > 0)
+            }
+            // A colon right after a name or a closing bracket accesses a
+            // field or slices an array instead, e.g. `payload:id` or `a[1:2]`
+            ':' if styles.contains(&ParameterStyle::Colon) => {
+                let follows_operand: bool = self.position >= 2
+                    && matches!(
+                        self.input[self.position - 2],
+                        ch if ch.is_alphanumeric() || matches!(ch, '_' | '"' | '`' | ']' | ')')
+                    );
+                Some(word_len(0)).filter(|&len| len > 0 && !follows_operand)
+            }
+            '@' if styles.contains(&ParameterStyle::At) => {
+                let system: usize = usize::from(self.peek(0) == Some('@'));
+                name_len(system).map(|len| system + len)
+            }
+            '%' if styles.contains(&ParameterStyle::Percent) => match self.peek(0) {
+                Some('s') if word_len(1) == 0 => Some(1),
+                Some('(') => {
+                    let len: usize = name_len(1)?;
+                    (self.peek(len + 1) == Some(')') && self.peek(len + 2) == Some('s'))
+                        .then_some(len + 3)
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    // Whether the character just consumed goes right after a name, a quoted
+    // identifier or a closing bracket, as in `arr[1]`, though not right after
+    // a reserved word, as in `SELECT[a]`
+    fn follows_operand(&self) -> bool {
+        let before: &[char] = &self.input[..self.position - 1];
+        match before.last() {
+            Some('"' | '`' | ']' | ')') => true,
+            Some(&ch) if self.dialect.is_identifier_part(ch) => {
+                let start: usize = before
+                    .iter()
+                    .rposition(|&ch| !self.dialect.is_identifier_part(ch))
+                    .map_or(0, |index| index + 1);
+                let word: String = before[start..].iter().collect();
+                !self.dialect.keywords().is_reserved(&word)
+            }
+            _ => false,
+        }
+    }
+
+    // Number of characters past the current position that belong to the
+    // stage reference opened by the `@` just consumed: the user stage `~`, a
+    // table stage `%name` or a possibly qualified stage name, followed by any
+    // `/path` up to the next space or delimiter
+    fn stage_len(&self) -> Option<usize> {
+        let mut len: usize = match self.peek(0)? {
+            '~' => 1,
+            '%' => 1,
+            ch if self.dialect.is_identifier_start(ch) => 0,
+            _ => return None,
+        };
+        let name_start: usize = len;
+        while self
+            .peek(len)
+            .is_some_and(|ch| self.dialect.is_identifier_part(ch) || matches!(ch, '.' | '$'))
+        {
+            len += 1;
+        }
+        if self.peek(0) == Some('%') && len == name_start {
+            return None;
+        }
+
+        if self.peek(len) == Some('/') {
+            while self.peek(len).is_some_and(|ch| {
+                !ch.is_whitespace() && !matches!(ch, ',' | ';' | '(' | ')' | '\'')
+            }) {
+                len += 1;
+            }
+        }
+        Some(len)
+    }
+
+    // Helper function to read a PostgreSQL dollar quoted string, whose body is
+    // taken verbatim up to the next occurrence of its opening delimiter
+    fn read_dollar_quoted_string(&mut self) -> Result<Token, LexError> {
+        let tag_len: usize = self.dollar_tag_len().expect("caller checked for a tag");
+        let start: usize = self.position - 1;
+        let delimiter: Vec<char> = self.input[start..=start + tag_len].to_vec();
+        self.position += tag_len;
+
+        loop {
+            if self.position >= self.input.len() {
+                return Err(self.error("unterminated dollar quoted string"));
+            }
+            if self.input[self.position..].starts_with(&delimiter) {
+                let body: String = self.input[start + delimiter.len()..self.position]
+                    .iter()
+                    .collect();
+                self.position += delimiter.len();
+                return Ok(Token::DollarQuoted(DollarQuoted {
+                    tag: delimiter[1..delimiter.len() - 1].iter().collect(),
+                    body,
+                }));
+            }
+            self.position += 1;
+        }
+    }
+
+    // Helper function to read a numeric literal: integers, decimals (including
+    // a leading `.5`), exponents, `0x`/`0b` integers and `_` digit separators
+    fn read_number_literal(&mut self, first_char: char) -> Token {
+        let mut literal: String = String::new();
+        literal.push(first_char);
+
+        if first_char == '0'
+            && let Some(marker @ ('x' | 'X' | 'b' | 'B')) = self.peek(0)
+        {
+            let radix: u32 = if matches!(marker, 'x' | 'X') { 16 } else { 2 };
+            if self.peek(1).is_some_and(|ch| ch.is_digit(radix)) {
+                literal.push(marker);
+                self.position += 1;
+                self.read_digits(&mut literal, radix);
+                return Token::Literal(literal);
+            }
+        }
+
+        self.read_digits(&mut literal, 10);
+        if first_char != '.' && self.peek(0) == Some('.') && self.peek(1) != Some('.') {
+            literal.push('.');
+            self.position += 1;
+            self.read_digits(&mut literal, 10);
+        }
+
+        if let Some(marker @ ('e' | 'E')) = self.peek(0) {
+            let sign: Option<char> = self.peek(1).filter(|ch| matches!(ch, '+' | '-'));
+            let digit_offset: usize = if sign.is_some() { 2 } else { 1 };
+
+            if self
+                .peek(digit_offset)
+                .is_some_and(|ch| ch.is_ascii_digit())
+            {
+                literal.push(marker);
+                literal.extend(sign);
+                self.position += digit_offset;
+                self.read_digits(&mut literal, 10);
+            }
+        }
+
+        Token::Literal(literal)
+    }
+
+    // Helper function to read a run of digits in `radix`, where a single `_`
+    // may separate two digits (e.g. `1_000_000`)
+    fn read_digits(&mut self, literal: &mut String, radix: u32) {
+        while let Some(ch) = self.peek(0) {
+            let is_separator: bool = ch == '_'
+                && literal
+                    .chars()
+                    .last()
+                    .is_some_and(|last| last.is_digit(radix))
+                && self.peek(1).is_some_and(|next| next.is_digit(radix));
+
+            if !ch.is_digit(radix) && !is_separator {
+                break;
+            }
+            literal.push(ch);
+            self.position += 1;
+        }
+    }
+}
+
+/// Reads every token from `input`, stopping before the end-of-input token.
+pub fn tokenize(input: &str, dialect: &'static dyn Dialect) -> Result<Vec<SpannedToken>, LexError> {
+    let mut lexer: Lexer = Lexer::new(input, dialect);
+    let mut tokens: Vec<SpannedToken> = Vec::new();
+
+    loop {
+        let token: SpannedToken = lexer.next_token()?;
+        if token.token == Token::Eof {
+            return Ok(tokens);
+        }
+        tokens.push(token);
+    }
+}
+
+/// Reads every token from `input` like `tokenize`, but recovers from errors:
+/// the rest of the line an error occurs on becomes an invalid token and lexing
+/// carries on after it. Returns the tokens and the errors recovered from.
+pub fn tokenize_recovering(
+    input: &str,
+    dialect: &'static dyn Dialect,
+) -> (Vec<SpannedToken>, Vec<LexError>) {
+    let mut lexer: Lexer = Lexer::new(input, dialect);
+    let mut tokens: Vec<SpannedToken> = Vec::new();
+    let mut errors: Vec<LexError> = Vec::new();
+
+    loop {
+        let token: SpannedToken = match lexer.next_token() {
+            Ok(token) => token,
+            Err(err) => {
+                let (token, err): (SpannedToken, LexError) = lexer.recover(err);
+                errors.push(err);
+                token
+            }
+        };
+        if token.token == Token::Eof {
+            return (tokens, errors);
+        }
+        tokens.push(token);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialect::{
+        GenericDialect, MySqlDialect, PostgresDialect, SnowflakeDialect, SqlServerDialect,
+        SqliteDialect,
+    };
+
+    // Tokens of `input` without the whitespace between them
+    fn tokens(input: &str, dialect: &'static dyn Dialect) -> Vec<Token> {
+        tokenize(input, dialect)
+            .unwrap()
+            .into_iter()
+            .map(|spanned| spanned.token)
+            .filter(|token| *token != Token::Whitespace)
+            .collect()
+    }
+
+    fn literal(text: &str) -> Token {
+        Token::Literal(text.to_string())
+    }
+
+    #[test]
+    fn keeps_comments_verbatim() {
+        assert_eq!(
+            tokens("a -- note  \n/* x /* nested */ */", &PostgresDialect),
+            vec![
+                Token::Identifier("a".to_string()),
+                Token::Comment(Comment {
+                    kind: CommentKind::Line,
+                    text: "-- note".to_string(),
+                }),
+                Token::Comment(Comment {
+                    kind: CommentKind::Block,
+                    text: "/* x /* nested */ */".to_string(),
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn block_comments_nest_only_where_the_dialect_allows() {
+        let comment = |text: &str| {
+            Token::Comment(Comment {
+                kind: CommentKind::Block,
+                text: text.to_string(),
+            })
+        };
+        assert_eq!(
+            tokens("/* a /* b */", &MySqlDialect),
+            vec![comment("/* a /* b */")]
+        );
+        assert_eq!(
+            tokens("/* a /* b */ */", &SqlServerDialect),
+            vec![comment("/* a /* b */ */")]
+        );
+        assert!(tokenize("/* a /* b */", &PostgresDialect).is_err());
+    }
+
+    #[test]
+    fn hash_comments_only_in_mysql() {
+        let comment: Token = Token::Comment(Comment {
+            kind: CommentKind::Line,
+            text: "# note".to_string(),
+        });
+        assert_eq!(tokens("# note", &MySqlDialect), vec![comment]);
+        assert!(!matches!(
+            tokens("# note", &PostgresDialect)[0],
+            Token::Comment(_)
+        ));
+    }
+
+    #[test]
+    fn quoted_identifiers_per_dialect() {
+        let quoted = |text: &str| Token::QuotedIdentifier(text.to_string());
+        assert_eq!(
+            tokens(r#""a ""b""""#, &GenericDialect),
+            vec![quoted(r#""a ""b""""#)]
+        );
+        assert_eq!(tokens("`a b`", &MySqlDialect), vec![quoted("`a b`")]);
+        assert_eq!(tokens("[a]]b]", &SqlServerDialect), vec![quoted("[a]]b]")]);
+    }
+
+    #[test]
+    fn escaped_quotes_in_strings() {
+        assert_eq!(tokens("'it''s'", &GenericDialect), vec![literal("'it''s'")]);
+        assert_eq!(tokens(r"'it\'s'", &MySqlDialect), vec![literal(r"'it\'s'")]);
+        assert_eq!(
+            tokens(r"E'it\'s'", &PostgresDialect),
+            vec![literal(r"E'it\'s'")]
+        );
+    }
+
+    #[test]
+    fn unterminated_string_is_an_error_at_its_start() {
+        let err: LexError = tokenize("SELECT\n  'abc", &GenericDialect).unwrap_err();
+        assert_eq!(err.message, "unterminated string literal");
+        assert_eq!((err.span.start.line, err.span.start.column), (2, 3));
+    }
+
+    #[test]
+    fn numeric_literals() {
+        for number in [
+            "42",
+            "1.5",
+            ".5",
+            "1e10",
+            "2.5E-3",
+            "0x1F",
+            "0b101",
+            "1_000_000",
+        ] {
+            assert_eq!(tokens(number, &GenericDialect), vec![literal(number)]);
+        }
+    }
+
+    #[test]
+    fn longest_operator_wins() {
+        let operators: Vec<Token> = tokens("a>=b<>c::int->>'k'", &PostgresDialect)
+            .into_iter()
+            .filter(|token| matches!(token, Token::Operator(_)))
+            .collect();
+        assert_eq!(
+            operators,
+            [">=", "<>", "::", "->>"]
+                .map(|op| Token::Operator(op.to_string()))
+                .to_vec()
+        );
+    }
+
+    #[test]
+    fn lone_operator_prefix_does_not_panic() {
+        assert!(tokenize("SELECT a : b", &MySqlDialect).is_ok());
+    }
+
+    #[test]
+    fn spans_track_lines_and_columns() {
+        let spanned: Vec<SpannedToken> = tokenize("SELECT\n  é, b", &GenericDialect).unwrap();
+        let b: &SpannedToken = spanned.last().unwrap();
+        assert_eq!(b.token, Token::Identifier("b".to_string()));
+        assert_eq!((b.span.start.line, b.span.start.column), (2, 6));
+        assert_eq!(b.span.start.offset, "SELECT\n  é, ".len());
+        assert_eq!(b.span.end.column, 7);
+    }
+
+    #[test]
+    fn bind_parameters_per_dialect() {
+        let parameter = |text: &str| Token::Parameter(text.to_string());
+        assert_eq!(
+            tokens(":name $1", &PostgresDialect),
+            vec![parameter(":name"), parameter("$1")]
+        );
+        // `?` is a jsonb operator in PostgreSQL
+        assert_eq!(
+            tokens("a ? 'k'", &PostgresDialect)[1],
+            Token::Operator("?".to_string())
+        );
+        assert_eq!(tokens("?", &GenericDialect), vec![parameter("?")]);
+        assert_eq!(
+            tokens("@id ?1", &SqliteDialect),
+            vec![parameter("@id"), parameter("?1")]
+        );
+        assert_eq!(tokens("@id", &SqlServerDialect), vec![parameter("@id")]);
+    }
+
+    #[test]
+    fn dollar_quoted_strings() {
+        assert_eq!(
+            tokens("$fn$ SELECT '$$' $fn$", &PostgresDialect),
+            vec![Token::DollarQuoted(DollarQuoted {
+                tag: "fn".to_string(),
+                body: " SELECT '$$' ".to_string(),
+            })]
+        );
+        let err: LexError = tokenize("$$ abc", &PostgresDialect).unwrap_err();
+        assert_eq!(err.message, "unterminated dollar quoted string");
+    }
+
+    #[test]
+    fn sql_server_temp_tables_and_national_strings() {
+        assert_eq!(
+            tokens("#tmp ##global N'x'", &SqlServerDialect),
+            vec![
+                Token::Identifier("#tmp".to_string()),
+                Token::Identifier("##global".to_string()),
+                literal("N'x'"),
+            ]
+        );
+    }
+
+    #[test]
+    fn snowflake_stage_references() {
+        let stage = |text: &str| Token::Stage(text.to_string());
+        assert_eq!(
+            tokens(
+                "@my_stage/path/a.csv @~ @%orders @db.sch.s",
+                &SnowflakeDialect
+            ),
+            vec![
+                stage("@my_stage/path/a.csv"),
+                stage("@~"),
+                stage("@%orders"),
+                stage("@db.sch.s"),
+            ]
+        );
+        assert_eq!(
+            tokens("(@s)", &SnowflakeDialect)[1],
+            stage("@s"),
+            "a stage ends at a delimiter"
+        );
+    }
+
+    #[test]
+    fn brackets_quote_identifiers_or_subscript() {
+        let punct = |ch: char| Token::Punctuation(ch);
+        let ident = |text: &str| Token::Identifier(text.to_string());
+        assert_eq!(
+            tokens("arr[1]", &PostgresDialect),
+            vec![ident("arr"), punct('['), literal("1"), punct(']')]
+        );
+        assert_eq!(
+            tokens("arr[i]", &GenericDialect),
+            vec![ident("arr"), punct('['), ident("i"), punct(']')]
+        );
+        assert_eq!(
+            tokens("SELECT [a b]", &GenericDialect)[1],
+            Token::QuotedIdentifier("[a b]".to_string())
+        );
+        assert_eq!(
+            tokens("a[b]", &SqlServerDialect)[1],
+            Token::QuotedIdentifier("[b]".to_string()),
+            "brackets only quote identifiers in T-SQL"
+        );
+    }
+
+    #[test]
+    fn recovery_skips_the_rest_of_the_line() {
+        let (spanned, errors): (Vec<SpannedToken>, Vec<LexError>) =
+            tokenize_recovering("SELECT 'abc\nFROM t", &GenericDialect);
+        let tokens: Vec<Token> = spanned
+            .into_iter()
+            .map(|spanned| spanned.token)
+            .filter(|token| *token != Token::Whitespace)
+            .collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].message, "unterminated string literal");
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Keyword("SELECT".to_string()),
+                Token::Invalid("'abc".to_string()),
+                Token::Keyword("FROM".to_string()),
+                Token::Identifier("t".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,59 @@
+//! Formats SQL source text.
+//!
+//! [`format()`] lays out SQL according to [`FormatOptions`]; [`tokenize`]
+//! and [`Lexer`] expose the tokens it works from. Everything exported here
+//! follows semantic versioning: enums and option structs are
+//! `#[non_exhaustive]`, so new variants, dialects and options are added in
+//! minor releases, while removals and changes in meaning wait for a major one.
+//! The exact layout of formatted output is not part of that promise and may
+//! improve in any release.
+
+#![warn(missing_docs)]
+
+mod ast;
+mod dialect;
+mod doc;
+mod error;
+mod formatter;
+mod keywords;
+mod lexer;
+mod options;
+mod parser;
+mod splitter;
+
+pub use dialect::{
+    BigQueryDialect, Dialect, GenericDialect, MySqlDialect, ParameterStyle, PostgresDialect,
+    SnowflakeDialect, SqlDialect, SqlServerDialect, SqliteDialect,
+};
+pub use error::FormatError;
+pub use keywords::{KeywordSet, Reservation};
+pub use lexer::{
+    Comment, CommentKind, DollarQuoted, LexError, Lexer, Location, Span, SpannedToken, Token,
+    tokenize, tokenize_recovering,
+};
+pub use options::{CaseStyle, CommaPosition, FormatOptions, IndentStyle};
+
+use formatter::Formatter;
+
+/// Formats a whole source text of one or more statements, in the dialect
+/// given by `options`. Fails only when the source cannot be tokenized;
+/// statements the parser does not understand are laid out token by token.
+pub fn format(sql: &str, options: &FormatOptions) -> Result<String, FormatError> {
+    let tokens: Vec<SpannedToken> = tokenize(sql, options.dialect.dialect())?;
+    let mut formatter: Formatter = Formatter::new(tokens, options.clone());
+    Ok(formatter.format())
+}
+
+/// Formats a whole source text like [`format()`], but carries on past source
+/// that cannot be tokenized: the rest of the line it is on is kept verbatim
+/// and the statement holding it is laid out token by token, while all other
+/// statements are formatted as usual. Returns the formatted text together
+/// with the errors recovered from, which [`FormatError::render`] turns into
+/// messages quoting the source.
+pub fn format_recovering(sql: &str, options: &FormatOptions) -> (String, Vec<FormatError>) {
+    let (tokens, errors): (Vec<SpannedToken>, Vec<LexError>) =
+        tokenize_recovering(sql, options.dialect.dialect());
+    let mut formatter: Formatter = Formatter::new(tokens, options.clone());
+    let errors: Vec<FormatError> = errors.into_iter().map(FormatError::from).collect();
+    (formatter.format(), errors)
+}
@@ -1,228 +1,317 @@
-use std::io::{self, Read};
-
-// Enum to represent different SQL tokens
-#[derive(Debug, PartialEq, Clone)]
-enum Token {
-    Keyword(String),
-    Identifier(String),
-    Literal(String),
-    Operator(String),
-    Punctuation(char),
-    Whitespace,
-    EOF,
+mod cli;
+mod config;
+mod diff;
+mod files;
+
+use std::env;
+use std::fs;
+use std::io::{self, IsTerminal, Read};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::Parser;
+use cli::{Cli, ColorChoice};
+use config::{Config, ConfigResolver};
+use files::FileFilter;
+use sql_formatter::{FormatError, FormatOptions};
+
+// Where a piece of SQL is read from
+enum Input {
+    Stdin,
+    File(PathBuf),
 }
 
-// Lexer struct to handle main tokenization
-struct Lexer {
-    input: Vec<char>,
-    position: usize,
-}
-
-impl Lexer {
-    // Create a new Lexer instance
-    fn new(input: &str) -> Self {
-        Lexer {
-            input: input.chars().collect(),
-            position: 0,
+impl Input {
+    // Name used for this input in messages
+    fn name(&self, cli: &Cli) -> String {
+        match self {
+            Input::Stdin => cli
+                .stdin_filename
+                .clone()
+                .unwrap_or_else(|| String::from("<stdin>")),
+            Input::File(path) => path.display().to_string(),
         }
     }
 
-    // Gets next character from input string
-    fn next_token(&mut self) -> Token {
-        if self.position >= self.input.len() {
-            return Token::EOF;
+    fn read(&self) -> io::Result<String> {
+        match self {
+            Input::Stdin => {
+                let mut buffer: String = String::new();
+                io::stdin().read_to_string(&mut buffer)?;
+                Ok(buffer)
+            }
+            Input::File(path) => fs::read_to_string(path),
         }
+    }
 
-        let ch: char = self.input[self.position];
-        self.position += 1;
-
-        match ch {
-            ' ' | '\t' | '\n' | '\r' => Token::Whitespace,
-            ',' | ';' | '(' | ')' => Token::Punctuation(ch),
-            '+' | '-' | '*' | '/' | '=' | '<' | '>' => Token::Operator(ch.to_string()),
-            '\'' => self.read_string_literal(),
-            _ if ch.is_alphabetic() => self.read_identifier(ch),
-            _ if ch.is_digit(10) => self.read_number_literal(ch),
-            _ => Token::Identifier(ch.to_string()),
-        }
+    // Directory to look for configuration from: the file's own directory, or
+    // for standard input the one `--stdin-filename` names, else the current one
+    fn config_dir(&self, cli: &Cli) -> PathBuf {
+        let path: Option<&Path> = match self {
+            Input::Stdin => cli.stdin_filename.as_deref().map(Path::new),
+            Input::File(path) => Some(path),
+        };
+        path.and_then(Path::parent)
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .map_or_else(|| PathBuf::from("."), Path::to_path_buf)
     }
 
-    // Helper function to read a complete idetifier or keyword
-    fn read_identifier(&mut self, first_char: char) -> Token {
-        let mut ident: String = String::new();
-        ident.push(first_char);
+    // Formats this input's source with the options that apply to it, carrying
+    // on past source that cannot be tokenized. Returns the formatted text and
+    // the errors recovered from, rendered with the source they point at.
+    fn format(
+        &self,
+        cli: &Cli,
+        resolver: &mut ConfigResolver,
+        source: &str,
+    ) -> Result<(String, Vec<String>), String> {
+        let options: FormatOptions = resolver
+            .options_for(&self.config_dir(cli))
+            .map_err(|err| err.to_string())?;
+        let (formatted_sql, errors): (String, Vec<FormatError>) =
+            sql_formatter::format_recovering(source, &options);
+        let name: String = self.name(cli);
+        let errors: Vec<String> = errors.iter().map(|err| err.render(&name, source)).collect();
+        Ok((formatted_sql, errors))
+    }
 
-        while self.position < self.input.len() && self.input[self.position].is_alphanumeric() {
-            ident.push(self.input[self.position]);
-            self.position += 1;
+    // Formats this input's source the way it is written back to a file:
+    // ending in a line break, with blank sources left as they are. A source
+    // that could only be formatted in part is an error.
+    fn format_contents(
+        &self,
+        cli: &Cli,
+        resolver: &mut ConfigResolver,
+        source: &str,
+    ) -> Result<String, String> {
+        if source.trim().is_empty() {
+            return Ok(source.to_string());
         }
-
-        match ident.to_uppercase().as_str() {
-            "SELECT" | "FROM" | "WHERE" | "AND" | "OR" | "INSERT" | "INTO" | "UPDATE" | "SET"
-            | "DELETE" | "JOIN" | "LEFT" | "RIGHT" | "OUTER" | "INNER" | "ON" | "GROUP" | "BY"
-            | "ORDER" | "HAVING" | "AS" | "CREATE" | "TABLE" | "DROP" | "ALTER" => {
-                Token::Keyword(ident.to_uppercase())
-            }
-            _ => Token::Identifier(ident),
+        let (formatted_sql, errors): (String, Vec<String>) = self.format(cli, resolver, source)?;
+        if !errors.is_empty() {
+            // Each error after the first gets its own heading, like the first
+            // gets from the caller
+            return Err(errors.join(&format!("\nError: {}: ", self.name(cli))));
         }
+        Ok(formatted_sql + "\n")
     }
+}
 
-    // Helper funciton to read a string literal enclosed in single quotes
-    fn read_string_literal(&mut self) -> Token {
-        let mut literal: String = String::new();
-        literal.push('\'');
+// Turns the command line paths into inputs, expanding directories into the
+// files under them that pass the include/exclude filters
+fn collect_inputs(cli: &Cli) -> Result<Vec<Input>, String> {
+    if cli.files.is_empty() {
+        return Ok(vec![Input::Stdin]);
+    }
 
-        while self.position < self.input.len() && self.input[self.position] != '\'' {
-            literal.push(self.input[self.position]);
-            self.position += 1;
+    let filter: FileFilter =
+        FileFilter::new(&cli.include, &cli.exclude).map_err(|err| err.to_string())?;
+    let mut inputs: Vec<Input> = Vec::new();
+
+    for path in &cli.files {
+        if path == Path::new("-") {
+            inputs.push(Input::Stdin);
+        } else if path.is_dir() {
+            let found: Vec<PathBuf> = filter
+                .collect(path)
+                .map_err(|err| format!("{}: {}", path.display(), err))?;
+            inputs.extend(found.into_iter().map(Input::File));
+        } else {
+            inputs.push(Input::File(path.clone()));
         }
+    }
+    Ok(inputs)
+}
 
-        if self.position < self.input.len() {
-            literal.push(self.input[self.position]);
-            self.position += 1;
+// Formats every file in place, reporting the ones that changed. Returns
+// whether every file could be formatted.
+fn write_in_place(cli: &Cli, resolver: &mut ConfigResolver, inputs: &[Input]) -> bool {
+    let mut succeeded: bool = true;
+    let mut changed: usize = 0;
+    let mut unchanged: usize = 0;
+
+    for input in inputs {
+        let Input::File(path) = input else {
+            eprintln!("Error: --write cannot be used with standard input");
+            succeeded = false;
+            continue;
+        };
+
+        let result: Result<bool, String> = fs::read_to_string(path)
+            .map_err(|err| err.to_string())
+            .and_then(|source| {
+                let formatted_sql: String = input.format_contents(cli, resolver, &source)?;
+                if formatted_sql == source {
+                    return Ok(false);
+                }
+                files::write_atomically(path, &formatted_sql).map_err(|err| err.to_string())?;
+                Ok(true)
+            });
+
+        match result {
+            Ok(true) => {
+                println!("Formatted {}", path.display());
+                changed += 1;
+            }
+            Ok(false) => unchanged += 1,
+            Err(err) => {
+                eprintln!("Error: {}: {}", input.name(cli), err);
+                succeeded = false;
+            }
         }
-        Token::Literal(literal)
     }
 
-    // Helper function to read a numeric literal
-    fn read_number_literal(&mut self, first_char: char) -> Token {
-        let mut literal: String = String::new();
-        literal.push(first_char);
+    if !cli.quiet {
+        println!("{} file(s) reformatted, {} unchanged", changed, unchanged);
+    }
+    succeeded
+}
 
-        while self.position < self.input.len() && self.input[self.position].is_digit(10) {
-            literal.push(self.input[self.position]);
-            self.position += 1;
+// Checks that every input is already formatted, printing a diff for each one
+// that is not. Returns whether all inputs were formatted.
+fn check_formatted(cli: &Cli, resolver: &mut ConfigResolver, inputs: &[Input]) -> bool {
+    let color: bool = match cli.color {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none(),
+    };
+    let mut succeeded: bool = true;
+    let mut unformatted: usize = 0;
+
+    for input in inputs {
+        let name: String = input.name(cli);
+        let result: Result<(String, String), String> = input
+            .read()
+            .map_err(|err| err.to_string())
+            .and_then(|source| Ok((input.format_contents(cli, resolver, &source)?, source)));
+
+        match result {
+            Ok((formatted_sql, source)) if formatted_sql != source => {
+                print!(
+                    "{}",
+                    diff::unified_diff(&name, &source, &formatted_sql, color)
+                );
+                unformatted += 1;
+                succeeded = false;
+            }
+            Ok(_) => {}
+            Err(err) => {
+                eprintln!("Error: {}: {}", name, err);
+                succeeded = false;
+            }
         }
-
-        Token::Literal(literal)
     }
-}
 
-struct Formatter {
-    tokens: Vec<Token>,
-    indent_level: usize,
-    output: String,
+    if !cli.quiet {
+        eprintln!("{} file(s) would be reformatted", unformatted);
+    }
+    succeeded
 }
 
-impl Formatter {
-    // Creates new formatter instance
-    fn new(tokens: Vec<Token>) -> Self {
-        Formatter {
-            tokens,
-            indent_level: 0,
-            output: String::new(),
+// Formats every input and prints the results, or writes them to `--output`.
+// Returns whether every input could be formatted.
+fn print_formatted(cli: &Cli, resolver: &mut ConfigResolver, inputs: &[Input]) -> bool {
+    let mut output: String = String::new();
+    let mut succeeded: bool = true;
+
+    for input in inputs {
+        // Only someone typing at a terminal is prompted, and never on stdout
+        if let Input::Stdin = input
+            && !cli.quiet
+            && io::stdin().is_terminal()
+        {
+            eprintln!(
+                "Enter SQL statement to format and press ctrl+D (mac/linux) or ctrl+Z (windows) when done:"
+            );
         }
-    }
 
-    // Function to format SQL
-    fn format(&mut self) -> String {
-        let mut last_token: Option<Token> = None;
-        let tokens: Vec<Token> = self.tokens.clone();
-
-        for token in &tokens {
-            match token {
-                Token::Keyword(kw) => match kw.as_str() {
-                    "SELECT" | "FROM" | "WHERE" | "UPDATE" | "SET" | "GROUP" | "ORDER" | "LEFT"
-                    | "RIGHT" | "INNER" => {
-                        self.new_line();
-                        self.append_token(token);
-                        self.new_line();
-                        self.indent_level += 1;
-                    }
-                    "AND" | "OR" => {
-                        self.new_line();
-                        self.append_token(token);
-                    }
-                    _ => {
-                        self.append_with_space(token, &last_token);
-                    }
-                },
-                Token::Punctuation('(') => {
-                    self.append_with_space(token, &last_token);
-                    self.indent_level += 1;
-                    self.new_line();
-                }
-                Token::Punctuation(')') => {
-                    self.indent_level -= 1;
-                    self.new_line();
-                    self.append_token(token);
-                }
-                Token::Punctuation(',') => {
-                    self.append_token(token);
-                    self.new_line();
-                }
-                Token::Whitespace => { /* IGNORE WHITESPACE */ }
-                _ => {
-                    self.append_with_space(token, &last_token);
-                }
+        let source: String = match input.read() {
+            Ok(source) => source,
+            Err(err) => {
+                eprintln!("Error: {}: {}", input.name(cli), err);
+                succeeded = false;
+                continue;
             }
+        };
 
-            if token != &Token::Whitespace {
-                last_token = Some(token.clone());
+        if source.trim().is_empty() {
+            if let Input::Stdin = input
+                && !cli.quiet
+            {
+                eprintln!("No input provided!!");
             }
+            continue;
         }
 
-        self.output.trim().to_string()
-    }
-
-    fn append_token(&mut self, token: &Token) {
-        let _: String = "\t".repeat(self.indent_level);
-        match token {
-            Token::Keyword(s) | Token::Identifier(s) | Token::Literal(s) | Token::Operator(s) => {
-                self.output.push_str(s)
+        match input.format(cli, resolver, &source) {
+            Ok((formatted_sql, errors)) => {
+                // The statements around an error are still formatted and printed
+                for err in &errors {
+                    eprintln!("Error: {}: {}", input.name(cli), err);
+                    succeeded = false;
+                }
+                // Output that is piped on gets just the SQL
+                if !cli.quiet && cli.output.is_none() && io::stdout().is_terminal() {
+                    output.push_str("\n---Formatted SQL---\n\n");
+                }
+                output.push_str(&formatted_sql);
+                output.push('\n');
             }
-            Token::Punctuation(c) => self.output.push(*c),
-            _ => {}
-        }
-    }
-
-    fn append_with_space(&mut self, token: &Token, last_token: &Option<Token>) {
-        if let Some(last) = last_token {
-            match last {
-                Token::Punctuation('(') => {}
-                _ => self.output.push(' '),
+            Err(err) => {
+                eprintln!("Error: {}: {}", input.name(cli), err);
+                succeeded = false;
             }
         }
-        self.append_token(token);
     }
 
-    fn new_line(&mut self) {
-        if !self.output.is_empty() && self.output.ends_with('\n') {
-            self.output.push('\n');
+    match &cli.output {
+        // Leave an existing output file alone rather than replace it with partial results
+        Some(_) if !succeeded => {}
+        Some(path) => {
+            if let Err(err) = fs::write(path, &output) {
+                eprintln!("Error: {}: {}", path.display(), err);
+                succeeded = false;
+            }
         }
-
-        let indent: String = "\t".repeat(self.indent_level);
-        self.output.push_str(&indent);
+        None => print!("{}", output),
     }
+    succeeded
 }
 
-fn main() {
-    println!(
-        "Enter SQL statement to format and press ctrl+D (mac/linux) or ctrl+Z (windows) when done:"
-    );
-
-    let mut buffer: String = String::new();
-    io::stdin().read_to_string(&mut buffer).unwrap();
-
-    if buffer.trim().is_empty() {
-        println!("No input provided!!");
-        return;
-    }
-
-    let mut lexer: Lexer = Lexer::new(&buffer);
-    let mut tokens: Vec<_> = Vec::new();
+fn main() -> ExitCode {
+    let cli: Cli = Cli::parse();
 
-    loop {
-        let token: Token = lexer.next_token();
-        if token == Token::EOF {
-            break;
+    let inputs: Vec<Input> = match collect_inputs(&cli) {
+        Ok(inputs) => inputs,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            return ExitCode::FAILURE;
         }
-        tokens.push(token);
+    };
+
+    let explicit: Option<Config> = match &cli.config {
+        Some(path) => match config::load(path) {
+            Ok(config) => Some(config),
+            Err(err) => {
+                eprintln!("Error: {}", err);
+                return ExitCode::FAILURE;
+            }
+        },
+        None => None,
+    };
+    let mut resolver: ConfigResolver = ConfigResolver::new(explicit, cli.overrides());
+
+    let succeeded: bool = if cli.write {
+        write_in_place(&cli, &mut resolver, &inputs)
+    } else if cli.check {
+        check_formatted(&cli, &mut resolver, &inputs)
+    } else {
+        print_formatted(&cli, &mut resolver, &inputs)
+    };
+
+    if succeeded {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
     }
-
-    let mut formatter: Formatter = Formatter::new(tokens);
-    let formatted_sql: String = formatter.format();
-
-    println!("\n---Formatted SQL---\n");
-    println!("{}", formatted_sql);
 }
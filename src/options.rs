@@ -0,0 +1,148 @@
+#[cfg(feature = "cli")]
+use clap::ValueEnum;
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+
+use crate::dialect::SqlDialect;
+
+/// Whether each indent level is a tab or a run of spaces.
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize),
+    serde(rename_all = "lowercase")
+)]
+#[non_exhaustive]
+pub enum IndentStyle {
+    /// One tab per level.
+    Tabs,
+    /// A run of spaces per level, as many as the indent width.
+    Spaces,
+}
+
+/// How the letters of a word are cased on output.
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize),
+    serde(rename_all = "lowercase")
+)]
+#[non_exhaustive]
+pub enum CaseStyle {
+    /// All letters upper case.
+    Upper,
+    /// All letters lower case.
+    Lower,
+    /// Letters kept as written.
+    Preserve,
+    /// First letter of each word upper case, the rest lower case.
+    Capitalize,
+}
+
+impl CaseStyle {
+    /// Returns `text` in this case.
+    pub fn apply(&self, text: &str) -> String {
+        match self {
+            CaseStyle::Upper => text.to_uppercase(),
+            CaseStyle::Lower => text.to_lowercase(),
+            CaseStyle::Preserve => text.to_string(),
+            CaseStyle::Capitalize => {
+                let mut result: String = String::with_capacity(text.len());
+                let mut word_start: bool = true;
+                for ch in text.chars() {
+                    if word_start {
+                        result.extend(ch.to_uppercase());
+                    } else {
+                        result.extend(ch.to_lowercase());
+                    }
+                    word_start = !(ch.is_alphanumeric() || ch == '_');
+                }
+                result
+            }
+        }
+    }
+}
+
+/// Where the commas separating list items go when a list is broken over lines.
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize),
+    serde(rename_all = "lowercase")
+)]
+#[non_exhaustive]
+pub enum CommaPosition {
+    /// At the end of each line but the last.
+    Trailing,
+    /// At the start of each line but the first.
+    Leading,
+}
+
+/// Settings that control how the formatter lays out SQL.
+///
+/// The struct is `#[non_exhaustive]`: start from [`FormatOptions::default`]
+/// and set the fields to change.
+#[derive(Debug, PartialEq, Clone)]
+#[non_exhaustive]
+pub struct FormatOptions {
+    /// Dialect whose quoting, operators, keywords and comments the input uses.
+    pub dialect: SqlDialect,
+    /// Whether indent levels are tabs or spaces.
+    pub indent_style: IndentStyle,
+    /// Spaces per indent level; with tabs, the width a tab is assumed to take up.
+    pub indent_width: usize,
+    /// Column lines are kept within where the layout allows; a line only runs
+    /// past it when a single word or comment is too long to fit.
+    pub max_line_width: usize,
+    /// Number of blank lines written between two statements.
+    pub lines_between_statements: usize,
+    /// Where list commas go when a list is broken over lines.
+    pub comma_position: CommaPosition,
+    /// Case of keywords.
+    pub keyword_case: CaseStyle,
+    /// Case of data type names.
+    pub data_type_case: CaseStyle,
+    /// Case of built-in function names; other function names are identifiers.
+    pub function_case: CaseStyle,
+    /// Case of unquoted identifiers; quoted ones are always kept as written.
+    pub identifier_case: CaseStyle,
+    /// Whether a CASE expression that fits on the line is kept on it, rather
+    /// than always putting each branch on a line of its own.
+    pub inline_short_case: bool,
+    /// Whether the dollar quoted bodies of `LANGUAGE sql` functions are
+    /// formatted as SQL too, rather than kept as written. Bodies in other
+    /// languages, such as PL/pgSQL, are always kept as written.
+    pub format_function_bodies: bool,
+}
+
+impl FormatOptions {
+    /// Text written for one level of indentation.
+    pub fn indent_unit(&self) -> String {
+        match self.indent_style {
+            IndentStyle::Tabs => String::from("\t"),
+            IndentStyle::Spaces => " ".repeat(self.indent_width),
+        }
+    }
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        FormatOptions {
+            dialect: SqlDialect::Generic,
+            indent_style: IndentStyle::Tabs,
+            indent_width: 4,
+            max_line_width: 80,
+            lines_between_statements: 1,
+            comma_position: CommaPosition::Trailing,
+            keyword_case: CaseStyle::Upper,
+            data_type_case: CaseStyle::Preserve,
+            function_case: CaseStyle::Preserve,
+            identifier_case: CaseStyle::Preserve,
+            inline_short_case: false,
+            format_function_bodies: false,
+        }
+    }
+}
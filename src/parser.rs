@@ -0,0 +1,1522 @@
+use std::fmt;
+
+use crate::ast::*;
+use crate::dialect::Dialect;
+use crate::lexer::{Location, Span, SpannedToken, Token};
+
+// Keywords that stand for a value on their own
+const VALUE_KEYWORDS: &[&str] = &[
+    "NULL",
+    "TRUE",
+    "FALSE",
+    "DEFAULT",
+    "CURRENT_DATE",
+    "CURRENT_TIME",
+    "CURRENT_TIMESTAMP",
+    "CURRENT_USER",
+    "LOCALTIME",
+    "LOCALTIMESTAMP",
+    "SESSION_USER",
+];
+
+const COMPARISON_OPERATORS: &[&str] = &["=", "<>", "!=", "<", ">", "<=", ">=", "<=>", "=="];
+
+// Words that start a table constraint rather than a column in CREATE TABLE
+const CONSTRAINT_KEYWORDS: &[&str] = &[
+    "CONSTRAINT",
+    "PRIMARY",
+    "UNIQUE",
+    "FOREIGN",
+    "CHECK",
+    "KEY",
+    "INDEX",
+    "EXCLUDE",
+];
+
+// Error raised when the tokens of a statement do not form SQL the parser understands
+#[derive(Debug, PartialEq, Clone)]
+pub struct ParseError {
+    pub message: String,
+    pub span: Span,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at line {}, column {}",
+            self.message, self.span.start.line, self.span.start.column
+        )
+    }
+}
+
+type ParseResult<T> = Result<T, ParseError>;
+
+// How deeply expressions and queries may nest before parsing gives up, low
+// enough that the recursion fits the 2 MiB stack of a spawned thread even in
+// a debug build
+const MAX_DEPTH: usize = 32;
+
+// Reserved keywords that are also names of functions, e.g. `LEFT(name, 3)`
+const FUNCTION_KEYWORDS: &[&str] = &["CHAR", "IF", "LEFT", "REPLACE", "RIGHT", "VALUES"];
+
+// Recursive descent parser over the significant (non-whitespace, non-comment)
+// tokens of a single statement
+pub struct Parser<'a> {
+    tokens: &'a [SpannedToken],
+    index: usize,
+    dialect: &'static dyn Dialect,
+    // Expressions and queries being parsed around the current position
+    depth: usize,
+    // Whether a subscript is being parsed, where `:` separates the bounds of
+    // a slice rather than accessing a path
+    in_subscript: bool,
+}
+
+impl<'a> Parser<'a> {
+    // Creates a parser for the tokens of one statement, without its `;`
+    pub fn new(tokens: &'a [SpannedToken], dialect: &'static dyn Dialect) -> Self {
+        Parser {
+            tokens,
+            index: 0,
+            dialect,
+            depth: 0,
+            in_subscript: false,
+        }
+    }
+
+    // Runs `parse` one nesting level deeper, failing once nesting goes past
+    // MAX_DEPTH
+    fn nested<T>(&mut self, parse: impl FnOnce(&mut Self) -> ParseResult<T>) -> ParseResult<T> {
+        if self.depth >= MAX_DEPTH {
+            return Err(self.error("nested too deeply"));
+        }
+        self.depth += 1;
+        let result: ParseResult<T> = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    // Parses the whole token slice as a single statement
+    pub fn parse_statement(&mut self) -> ParseResult<Statement> {
+        let statement: Statement = if self.starts_query_at(0) {
+            Statement::Query(Box::new(self.parse_query()?))
+        } else if self.is_keyword("INSERT") {
+            Statement::Insert(self.parse_insert()?)
+        } else if self.is_keyword("UPDATE") {
+            Statement::Update(self.parse_update()?)
+        } else if self.is_keyword("DELETE") {
+            Statement::Delete(self.parse_delete()?)
+        } else if self.is_keyword("CREATE") {
+            self.parse_create()?
+        } else {
+            return Err(self.error("expected a statement"));
+        };
+
+        if self.peek() != &Token::Eof {
+            return Err(self.error("unexpected token"));
+        }
+        Ok(statement)
+    }
+
+    // ---- Token helpers ----
+
+    fn peek(&self) -> &'a Token {
+        self.peek_nth(0)
+    }
+
+    fn peek_nth(&self, n: usize) -> &'a Token {
+        self.tokens
+            .get(self.index + n)
+            .map_or(&Token::Eof, |spanned| &spanned.token)
+    }
+
+    // Span of the current token, or an empty span at the end of the input
+    fn span(&self) -> Span {
+        match self.tokens.get(self.index) {
+            Some(spanned) => spanned.span,
+            None => {
+                let end: Location = self.tokens.last().map_or(
+                    Location {
+                        offset: 0,
+                        line: 1,
+                        column: 1,
+                    },
+                    |spanned| spanned.span.end,
+                );
+                Span { start: end, end }
+            }
+        }
+    }
+
+    fn advance(&mut self) -> &'a SpannedToken {
+        let spanned: &SpannedToken = &self.tokens[self.index];
+        self.index += 1;
+        spanned
+    }
+
+    fn error(&self, message: &str) -> ParseError {
+        ParseError {
+            message: message.to_string(),
+            span: self.span(),
+        }
+    }
+
+    // Text of the token `n` places ahead if it is a bare word
+    fn word_at(&self, n: usize) -> Option<&'a str> {
+        match self.peek_nth(n) {
+            Token::Keyword(word) | Token::Identifier(word) => Some(word),
+            _ => None,
+        }
+    }
+
+    fn is_keyword_at(&self, n: usize, keyword: &str) -> bool {
+        self.word_at(n)
+            .is_some_and(|word| word.eq_ignore_ascii_case(keyword))
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        self.is_keyword_at(0, keyword)
+    }
+
+    fn is_punct(&self, ch: char) -> bool {
+        self.peek() == &Token::Punctuation(ch)
+    }
+
+    fn is_operator(&self, op: &str) -> bool {
+        matches!(self.peek(), Token::Operator(current) if current == op)
+    }
+
+    fn consume_punct(&mut self, ch: char) -> bool {
+        let found: bool = self.is_punct(ch);
+        if found {
+            self.index += 1;
+        }
+        found
+    }
+
+    fn expect_punct(&mut self, ch: char) -> ParseResult<()> {
+        if self.consume_punct(ch) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected `{ch}`")))
+        }
+    }
+
+    // Builds a keyword from the words read since `start`, spelled as in the source
+    fn keyword_since(&self, start: usize) -> Keyword {
+        let words: Vec<&str> = self.tokens[start..self.index]
+            .iter()
+            .filter_map(|spanned| match &spanned.token {
+                Token::Keyword(word) | Token::Identifier(word) => Some(word.as_str()),
+                _ => None,
+            })
+            .collect();
+
+        Keyword {
+            text: words.join(" "),
+            span: Span {
+                start: self.tokens[start].span.start,
+                end: self.tokens[self.index - 1].span.end,
+            },
+        }
+    }
+
+    // Consumes the given sequence of keywords if they all come next
+    fn parse_keywords(&mut self, keywords: &[&str]) -> Option<Keyword> {
+        let matches: bool = keywords
+            .iter()
+            .enumerate()
+            .all(|(n, keyword)| self.is_keyword_at(n, keyword));
+        if !matches {
+            return None;
+        }
+
+        let start: usize = self.index;
+        self.index += keywords.len();
+        Some(self.keyword_since(start))
+    }
+
+    fn parse_keyword(&mut self, keyword: &str) -> Option<Keyword> {
+        self.parse_keywords(&[keyword])
+    }
+
+    fn parse_one_of_keywords(&mut self, keywords: &[&str]) -> Option<Keyword> {
+        let keyword: &str = keywords.iter().find(|keyword| self.is_keyword(keyword))?;
+        self.parse_keyword(keyword)
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> ParseResult<Keyword> {
+        self.parse_keyword(keyword)
+            .ok_or_else(|| self.error(&format!("expected {keyword}")))
+    }
+
+    fn expect_keywords(&mut self, keywords: &[&str]) -> ParseResult<Keyword> {
+        self.parse_keywords(keywords)
+            .ok_or_else(|| self.error(&format!("expected {}", keywords.join(" "))))
+    }
+
+    fn parse_clause<T>(
+        &mut self,
+        keywords: &[&str],
+        parse_body: impl FnOnce(&mut Self) -> ParseResult<T>,
+    ) -> ParseResult<Option<Clause<T>>> {
+        match self.parse_keywords(keywords) {
+            Some(keyword) => Ok(Some(Clause {
+                keyword,
+                body: parse_body(self)?,
+            })),
+            None => Ok(None),
+        }
+    }
+
+    fn parse_comma_separated<T>(
+        &mut self,
+        mut parse_item: impl FnMut(&mut Self) -> ParseResult<T>,
+    ) -> ParseResult<Vec<T>> {
+        let mut items: Vec<T> = vec![parse_item(self)?];
+        while self.consume_punct(',') {
+            items.push(parse_item(self)?);
+        }
+        Ok(items)
+    }
+
+    // Parses `( item, ... )`
+    fn parse_parenthesized<T>(
+        &mut self,
+        parse_item: impl FnMut(&mut Self) -> ParseResult<T>,
+    ) -> ParseResult<Vec<T>> {
+        self.expect_punct('(')?;
+        let items: Vec<T> = self.parse_comma_separated(parse_item)?;
+        self.expect_punct(')')?;
+        Ok(items)
+    }
+
+    // Collects tokens up to a `,` or `)` that is not nested in parentheses
+    fn parse_raw_until_list_end(&mut self) -> Vec<SpannedToken> {
+        let start: usize = self.index;
+        self.skip_to_list_end();
+        self.tokens[start..self.index].to_vec()
+    }
+
+    // Collects tokens up to the `)` closing the current parentheses
+    fn parse_raw_until_close(&mut self) -> Vec<SpannedToken> {
+        let start: usize = self.index;
+        while !self.is_punct(')') && self.peek() != &Token::Eof {
+            self.skip_to_list_end();
+            self.consume_punct(',');
+        }
+        self.tokens[start..self.index].to_vec()
+    }
+
+    fn skip_to_list_end(&mut self) {
+        let mut depth: usize = 0;
+        loop {
+            match self.peek() {
+                Token::Eof => return,
+                Token::Punctuation(',' | ')') if depth == 0 => return,
+                Token::Punctuation('(') => depth += 1,
+                Token::Punctuation(')') => depth -= 1,
+                _ => {}
+            }
+            self.index += 1;
+        }
+    }
+
+    // Whether a query (rather than an expression) starts `n` tokens ahead
+    fn starts_query_at(&self, n: usize) -> bool {
+        self.is_keyword_at(n, "SELECT")
+            || self.is_keyword_at(n, "WITH")
+            || self.is_keyword_at(n, "VALUES")
+            || (self.peek_nth(n) == &Token::Punctuation('(') && self.starts_query_at(n + 1))
+    }
+
+    // ---- Names ----
+
+    // Reserved words end an expression or a list item rather than naming
+    // something, so they are never taken as an identifier or an implicit alias
+    fn is_reserved(&self, word: &str) -> bool {
+        self.dialect.keywords().is_reserved(word)
+    }
+
+    // Reads any word or quoted identifier, reserved or not
+    fn parse_word(&mut self) -> ParseResult<Ident> {
+        match self.peek() {
+            Token::Keyword(value)
+            | Token::Identifier(value)
+            | Token::QuotedIdentifier(value)
+            | Token::Stage(value) => {
+                let quoted: bool =
+                    matches!(self.peek(), Token::QuotedIdentifier(_) | Token::Stage(_));
+                let value: String = value.clone();
+                let span: Span = self.advance().span;
+                Ok(Ident {
+                    value,
+                    span,
+                    quoted,
+                })
+            }
+            _ => Err(self.error("expected an identifier")),
+        }
+    }
+
+    fn parse_identifier(&mut self) -> ParseResult<Ident> {
+        match self.word_at(0) {
+            Some(word) if self.is_reserved(word) => Err(self.error("expected an identifier")),
+            _ => self.parse_word(),
+        }
+    }
+
+    fn parse_object_name(&mut self) -> ParseResult<ObjectName> {
+        let mut parts: Vec<Ident> = vec![self.parse_identifier()?];
+        while self.consume_punct('.') {
+            parts.push(self.parse_word()?);
+        }
+        Ok(ObjectName(parts))
+    }
+
+    // Parses an optional alias; table aliases may also rename columns
+    fn parse_alias(&mut self, with_columns: bool) -> ParseResult<Option<Alias>> {
+        let keyword: Option<Keyword> = self.parse_keyword("AS");
+        let explicit: bool = keyword.is_some();
+        let name: Ident = match self.peek() {
+            Token::QuotedIdentifier(_) => self.parse_word()?,
+            Token::Literal(value) if explicit && value.starts_with('\'') => {
+                self.parse_word_literal()
+            }
+            Token::Keyword(word) | Token::Identifier(word) if !self.is_reserved(word) => {
+                self.parse_word()?
+            }
+            _ if explicit => return Err(self.error("expected an alias after AS")),
+            _ => return Ok(None),
+        };
+
+        let columns: Vec<Ident> = if with_columns && self.is_punct('(') {
+            self.parse_parenthesized(Self::parse_identifier)?
+        } else {
+            Vec::new()
+        };
+        Ok(Some(Alias {
+            keyword,
+            name,
+            columns,
+        }))
+    }
+
+    // Reads a string literal used as a name, e.g. `AS 'total'`
+    fn parse_word_literal(&mut self) -> Ident {
+        let spanned: &SpannedToken = self.advance();
+        let value: String = match &spanned.token {
+            Token::Literal(value) => value.clone(),
+            _ => unreachable!("caller checked for a literal"),
+        };
+        Ident {
+            value,
+            span: spanned.span,
+            quoted: true,
+        }
+    }
+
+    // ---- Statements ----
+
+    fn parse_create(&mut self) -> ParseResult<Statement> {
+        let start: usize = self.index;
+        self.expect_keyword("CREATE")?;
+        self.parse_keywords(&["OR", "REPLACE"]);
+        self.parse_one_of_keywords(&["TEMPORARY", "TEMP"]);
+
+        if self.parse_keyword("VIEW").is_some() {
+            let keyword: Keyword = self.keyword_since(start);
+            let name: ObjectName = self.parse_object_name()?;
+            let columns: Vec<Ident> = if self.is_punct('(') {
+                self.parse_parenthesized(Self::parse_identifier)?
+            } else {
+                Vec::new()
+            };
+            let as_keyword: Keyword = self.expect_keyword("AS")?;
+            let query: Query = self.parse_query()?;
+            return Ok(Statement::CreateView(CreateView {
+                keyword,
+                name,
+                columns,
+                query: Clause {
+                    keyword: as_keyword,
+                    body: Box::new(query),
+                },
+            }));
+        }
+
+        self.expect_keyword("TABLE")?;
+        self.parse_keywords(&["IF", "NOT", "EXISTS"]);
+        let keyword: Keyword = self.keyword_since(start);
+        let name: ObjectName = self.parse_object_name()?;
+
+        let elements: Vec<TableElement> = if self.is_punct('(') {
+            self.parse_parenthesized(Self::parse_table_element)?
+        } else {
+            Vec::new()
+        };
+        let query: Option<Clause<Box<Query>>> =
+            self.parse_clause(&["AS"], |p| Ok(Box::new(p.parse_query()?)))?;
+
+        let options: Vec<SpannedToken> = self.tokens[self.index..].to_vec();
+        self.index = self.tokens.len();
+
+        Ok(Statement::CreateTable(CreateTable {
+            keyword,
+            name,
+            elements,
+            query,
+            options,
+        }))
+    }
+
+    fn parse_table_element(&mut self) -> ParseResult<TableElement> {
+        if CONSTRAINT_KEYWORDS
+            .iter()
+            .any(|keyword| self.is_keyword(keyword))
+        {
+            return Ok(TableElement::Constraint(self.parse_raw_until_list_end()));
+        }
+
+        let name: Ident = self.parse_identifier()?;
+        let data_type: DataType = self.parse_data_type()?;
+        let options: Vec<SpannedToken> = self.parse_raw_until_list_end();
+        Ok(TableElement::Column(ColumnDef {
+            name,
+            data_type,
+            options,
+        }))
+    }
+
+    fn parse_insert(&mut self) -> ParseResult<Insert> {
+        let start: usize = self.index;
+        self.expect_keyword("INSERT")?;
+        self.parse_keyword("INTO");
+        let keyword: Keyword = self.keyword_since(start);
+
+        let table: ObjectName = self.parse_object_name()?;
+        let columns: Vec<Ident> = if self.is_punct('(') && !self.starts_query_at(0) {
+            self.parse_parenthesized(Self::parse_identifier)?
+        } else {
+            Vec::new()
+        };
+        let source: Query = self.parse_query()?;
+        let on_conflict: Option<OnConflict> =
+            if self.is_keyword("ON") && self.is_keyword_at(1, "CONFLICT") {
+                Some(self.parse_on_conflict()?)
+            } else {
+                None
+            };
+        let returning = self.parse_clause(&["RETURNING"], |parser| {
+            parser.parse_comma_separated(Self::parse_select_item)
+        })?;
+
+        Ok(Insert {
+            keyword,
+            table,
+            columns,
+            source: Box::new(source),
+            on_conflict,
+            returning,
+        })
+    }
+
+    fn parse_on_conflict(&mut self) -> ParseResult<OnConflict> {
+        let keyword: Keyword = self.expect_keywords(&["ON", "CONFLICT"])?;
+        let target: ConflictTarget = if self.is_punct('(') {
+            ConflictTarget::Columns(self.parse_parenthesized(Self::parse_expr)?)
+        } else if let Some(constraint) =
+            self.parse_clause(&["ON", "CONSTRAINT"], Self::parse_object_name)?
+        {
+            ConflictTarget::Constraint(constraint)
+        } else {
+            ConflictTarget::None
+        };
+
+        let action: ConflictAction = match self.parse_keywords(&["DO", "NOTHING"]) {
+            Some(keyword) => ConflictAction::Nothing(keyword),
+            None => {
+                let keyword: Keyword = self.expect_keywords(&["DO", "UPDATE"])?;
+                let assignments = self
+                    .parse_clause(&["SET"], |parser| {
+                        parser.parse_comma_separated(Self::parse_assignment)
+                    })?
+                    .ok_or_else(|| self.error("expected SET"))?;
+                let selection = self.parse_clause(&["WHERE"], Self::parse_expr)?;
+                ConflictAction::Update(Box::new(ConflictUpdate {
+                    keyword,
+                    assignments,
+                    selection,
+                }))
+            }
+        };
+
+        Ok(OnConflict {
+            keyword,
+            target,
+            action,
+        })
+    }
+
+    fn parse_update(&mut self) -> ParseResult<Update> {
+        let keyword: Keyword = self.expect_keyword("UPDATE")?;
+        let table: TableWithJoins = self.parse_table_with_joins()?;
+        let assignments = self
+            .parse_clause(&["SET"], |parser| {
+                parser.parse_comma_separated(Self::parse_assignment)
+            })?
+            .ok_or_else(|| self.error("expected SET"))?;
+        let from = self.parse_clause(&["FROM"], |parser| {
+            parser.parse_comma_separated(Self::parse_table_with_joins)
+        })?;
+        let selection = self.parse_clause(&["WHERE"], Self::parse_expr)?;
+        let returning = self.parse_clause(&["RETURNING"], |parser| {
+            parser.parse_comma_separated(Self::parse_select_item)
+        })?;
+
+        Ok(Update {
+            keyword,
+            table,
+            assignments,
+            from,
+            selection,
+            returning,
+        })
+    }
+
+    fn parse_assignment(&mut self) -> ParseResult<Assignment> {
+        let target: ObjectName = self.parse_object_name()?;
+        if !self.is_operator("=") {
+            return Err(self.error("expected `=`"));
+        }
+        self.index += 1;
+        let value: Expr = self.parse_expr()?;
+        Ok(Assignment { target, value })
+    }
+
+    fn parse_delete(&mut self) -> ParseResult<Delete> {
+        let start: usize = self.index;
+        self.expect_keyword("DELETE")?;
+        self.parse_keyword("FROM");
+        let keyword: Keyword = self.keyword_since(start);
+
+        let table: TableFactor = self.parse_table_factor()?;
+        let using = self.parse_clause(&["USING"], |parser| {
+            parser.parse_comma_separated(Self::parse_table_with_joins)
+        })?;
+        let selection = self.parse_clause(&["WHERE"], Self::parse_expr)?;
+        let returning = self.parse_clause(&["RETURNING"], |parser| {
+            parser.parse_comma_separated(Self::parse_select_item)
+        })?;
+
+        Ok(Delete {
+            keyword,
+            table,
+            using,
+            selection,
+            returning,
+        })
+    }
+
+    // ---- Queries ----
+
+    fn parse_query(&mut self) -> ParseResult<Query> {
+        self.nested(Self::parse_query_body)
+    }
+
+    fn parse_query_body(&mut self) -> ParseResult<Query> {
+        let with: Option<With> = if self.is_keyword("WITH") {
+            let keyword: Keyword = self
+                .parse_keywords(&["WITH", "RECURSIVE"])
+                .or_else(|| self.parse_keyword("WITH"))
+                .expect("checked for WITH");
+            let ctes: Vec<Cte> = self.parse_comma_separated(Self::parse_cte)?;
+            Some(With { keyword, ctes })
+        } else {
+            None
+        };
+
+        let body: SetExpr = self.parse_set_expr()?;
+        let order_by = self.parse_clause(&["ORDER", "BY"], |parser| {
+            parser.parse_comma_separated(Self::parse_order_by_expr)
+        })?;
+        let limit = self.parse_clause(&["LIMIT"], Self::parse_expr)?;
+        let offset = self.parse_clause(&["OFFSET"], Self::parse_expr)?;
+        let mut locks: Vec<Lock> = Vec::new();
+        while self.is_keyword("FOR") {
+            locks.push(self.parse_lock()?);
+        }
+
+        Ok(Query {
+            with,
+            body,
+            order_by,
+            limit,
+            offset,
+            locks,
+        })
+    }
+
+    fn parse_lock(&mut self) -> ParseResult<Lock> {
+        let keyword: Keyword = [
+            &["FOR", "UPDATE"][..],
+            &["FOR", "NO", "KEY", "UPDATE"],
+            &["FOR", "SHARE"],
+            &["FOR", "KEY", "SHARE"],
+        ]
+        .iter()
+        .find_map(|keywords| self.parse_keywords(keywords))
+        .ok_or_else(|| self.error("expected FOR UPDATE or FOR SHARE"))?;
+        let of = self.parse_clause(&["OF"], |parser| {
+            parser.parse_comma_separated(Self::parse_object_name)
+        })?;
+        let wait: Option<Keyword> = self
+            .parse_keyword("NOWAIT")
+            .or_else(|| self.parse_keywords(&["SKIP", "LOCKED"]));
+
+        Ok(Lock { keyword, of, wait })
+    }
+
+    fn parse_cte(&mut self) -> ParseResult<Cte> {
+        let name: Ident = self.parse_identifier()?;
+        let columns: Vec<Ident> = if self.is_punct('(') {
+            self.parse_parenthesized(Self::parse_identifier)?
+        } else {
+            Vec::new()
+        };
+        let keyword: Keyword = self.expect_keyword("AS")?;
+        self.expect_punct('(')?;
+        let query: Query = self.parse_query()?;
+        self.expect_punct(')')?;
+
+        Ok(Cte {
+            name,
+            columns,
+            query: Clause {
+                keyword,
+                body: Box::new(query),
+            },
+        })
+    }
+
+    fn parse_set_expr(&mut self) -> ParseResult<SetExpr> {
+        let mut expr: SetExpr = self.parse_set_operand()?;
+
+        while let Some(operator) = self.parse_set_operator() {
+            let right: SetExpr = self.parse_set_operand()?;
+            expr = SetExpr::SetOperation {
+                left: Box::new(expr),
+                operator,
+                right: Box::new(right),
+            };
+        }
+        Ok(expr)
+    }
+
+    fn parse_set_operator(&mut self) -> Option<Keyword> {
+        let start: usize = self.index;
+        self.parse_one_of_keywords(&["UNION", "EXCEPT", "INTERSECT", "MINUS"])?;
+        self.parse_one_of_keywords(&["ALL", "DISTINCT"]);
+        Some(self.keyword_since(start))
+    }
+
+    fn parse_set_operand(&mut self) -> ParseResult<SetExpr> {
+        if self.is_keyword("SELECT") {
+            Ok(SetExpr::Select(Box::new(self.parse_select()?)))
+        } else if self.is_keyword("VALUES") {
+            Ok(SetExpr::Values(self.parse_values()?))
+        } else if self.consume_punct('(') {
+            let query: Query = self.parse_query()?;
+            self.expect_punct(')')?;
+            Ok(SetExpr::Query(Box::new(query)))
+        } else {
+            Err(self.error("expected SELECT, VALUES or a parenthesized query"))
+        }
+    }
+
+    fn parse_select(&mut self) -> ParseResult<Select> {
+        let keyword: Keyword = self.expect_keyword("SELECT")?;
+        let quantifier: Option<Keyword> = self.parse_one_of_keywords(&["DISTINCT", "ALL"]);
+        let distinct_on = match &quantifier {
+            Some(quantifier) if quantifier.text.eq_ignore_ascii_case("DISTINCT") => self
+                .parse_clause(&["ON"], |parser| {
+                    parser.parse_parenthesized(Self::parse_expr)
+                })?,
+            _ => None,
+        };
+        let projection: Vec<SelectItem> = self.parse_comma_separated(Self::parse_select_item)?;
+        let from = self.parse_clause(&["FROM"], |parser| {
+            parser.parse_comma_separated(Self::parse_table_with_joins)
+        })?;
+        let selection = self.parse_clause(&["WHERE"], Self::parse_expr)?;
+        let group_by = self.parse_clause(&["GROUP", "BY"], |parser| {
+            parser.parse_comma_separated(Self::parse_expr)
+        })?;
+        let having = self.parse_clause(&["HAVING"], Self::parse_expr)?;
+
+        Ok(Select {
+            keyword,
+            quantifier,
+            distinct_on,
+            projection,
+            from,
+            selection,
+            group_by,
+            having,
+        })
+    }
+
+    fn parse_select_item(&mut self) -> ParseResult<SelectItem> {
+        let expr: Expr = self.parse_expr()?;
+        let alias: Option<Alias> = self.parse_alias(false)?;
+        Ok(SelectItem { expr, alias })
+    }
+
+    fn parse_values(&mut self) -> ParseResult<Values> {
+        let keyword: Keyword = self.expect_keyword("VALUES")?;
+        let rows: Vec<Vec<Expr>> =
+            self.parse_comma_separated(|parser| parser.parse_parenthesized(Self::parse_expr))?;
+        Ok(Values { keyword, rows })
+    }
+
+    fn parse_order_by_expr(&mut self) -> ParseResult<OrderByExpr> {
+        let expr: Expr = self.parse_expr()?;
+        let mut options: Vec<Keyword> = Vec::new();
+
+        if let Some(direction) = self.parse_one_of_keywords(&["ASC", "DESC"]) {
+            options.push(direction);
+        }
+        if let Some(nulls) = self
+            .parse_keywords(&["NULLS", "FIRST"])
+            .or_else(|| self.parse_keywords(&["NULLS", "LAST"]))
+        {
+            options.push(nulls);
+        }
+        Ok(OrderByExpr { expr, options })
+    }
+
+    // ---- Table references ----
+
+    fn parse_table_with_joins(&mut self) -> ParseResult<TableWithJoins> {
+        let relation: TableFactor = self.parse_table_factor()?;
+        let mut joins: Vec<Join> = Vec::new();
+
+        while let Some(operator) = self.parse_join_operator() {
+            let relation: TableFactor = self.parse_table_factor()?;
+            let constraint: JoinConstraint =
+                if let Some(on) = self.parse_clause(&["ON"], Self::parse_expr)? {
+                    JoinConstraint::On(on)
+                } else if let Some(using) = self.parse_clause(&["USING"], |p| {
+                    p.parse_parenthesized(Self::parse_identifier)
+                })? {
+                    JoinConstraint::Using(using)
+                } else {
+                    JoinConstraint::None
+                };
+            joins.push(Join {
+                operator,
+                relation,
+                constraint,
+            });
+        }
+        Ok(TableWithJoins { relation, joins })
+    }
+
+    // Reads e.g. `JOIN`, `NATURAL LEFT OUTER JOIN` or `CROSS APPLY`
+    fn parse_join_operator(&mut self) -> Option<Keyword> {
+        let start: usize = self.index;
+        self.parse_keyword("NATURAL");
+        self.parse_one_of_keywords(&["INNER", "LEFT", "RIGHT", "FULL", "CROSS"]);
+        self.parse_keyword("OUTER");
+
+        match self.parse_one_of_keywords(&["JOIN", "APPLY"]) {
+            Some(_) => Some(self.keyword_since(start)),
+            None => {
+                self.index = start;
+                None
+            }
+        }
+    }
+
+    fn parse_table_factor(&mut self) -> ParseResult<TableFactor> {
+        let lateral: Option<Keyword> = self.parse_keyword("LATERAL");
+
+        if self.is_punct('(') {
+            if lateral.is_some() || self.starts_query_at(1) {
+                self.index += 1;
+                let subquery: Query = self.parse_query()?;
+                self.expect_punct(')')?;
+                return Ok(TableFactor::Derived {
+                    lateral,
+                    subquery: Box::new(subquery),
+                    alias: self.parse_alias(true)?,
+                });
+            }
+
+            self.index += 1;
+            let table: TableWithJoins = self.parse_table_with_joins()?;
+            self.expect_punct(')')?;
+            return Ok(TableFactor::NestedJoin {
+                table: Box::new(table),
+                alias: self.parse_alias(true)?,
+            });
+        }
+
+        if lateral.is_some() {
+            return Err(self.error("expected a subquery after LATERAL"));
+        }
+
+        let name: ObjectName = self.parse_object_name()?;
+        let args: Option<Vec<Expr>> = if self.consume_punct('(') {
+            let args: Vec<Expr> = if self.is_punct(')') {
+                Vec::new()
+            } else {
+                self.parse_comma_separated(Self::parse_expr)?
+            };
+            self.expect_punct(')')?;
+            Some(args)
+        } else {
+            None
+        };
+
+        Ok(TableFactor::Table {
+            name,
+            args,
+            alias: self.parse_alias(true)?,
+        })
+    }
+
+    // ---- Expressions ----
+
+    pub fn parse_expr(&mut self) -> ParseResult<Expr> {
+        self.nested(Self::parse_or)
+    }
+
+    // Consumes a keyword operator such as `AND` if it comes next
+    fn parse_word_operator(&mut self, keyword: &str) -> Option<Operator> {
+        self.parse_keyword(keyword).map(|keyword| Operator {
+            text: keyword.text,
+            span: keyword.span,
+        })
+    }
+
+    fn binary(left: Expr, op: Operator, right: Expr) -> Expr {
+        Expr::BinaryOp {
+            left: Box::new(left),
+            op,
+            right: Box::new(right),
+        }
+    }
+
+    // Parses operands joined by the operators `parse_op` reads, all of one
+    // precedence, into a flat chain
+    fn parse_chain(
+        &mut self,
+        parse_operand: fn(&mut Self) -> ParseResult<Expr>,
+        parse_op: fn(&mut Self) -> Option<Operator>,
+    ) -> ParseResult<Expr> {
+        let first: Expr = parse_operand(self)?;
+        let mut rest: Vec<(Operator, Expr)> = Vec::new();
+        while let Some(op) = parse_op(self) {
+            rest.push((op, parse_operand(self)?));
+        }
+
+        if rest.is_empty() {
+            return Ok(first);
+        }
+        Ok(Expr::OperatorChain {
+            first: Box::new(first),
+            rest,
+        })
+    }
+
+    // Fails once `links` operators wrapping the expression on their left
+    // would nest it deeper than MAX_DEPTH
+    fn check_links(&self, links: usize) -> ParseResult<()> {
+        if self.depth + links >= MAX_DEPTH {
+            return Err(self.error("nested too deeply"));
+        }
+        Ok(())
+    }
+
+    fn parse_or(&mut self) -> ParseResult<Expr> {
+        self.parse_chain(Self::parse_and, |p| p.parse_word_operator("OR"))
+    }
+
+    fn parse_and(&mut self) -> ParseResult<Expr> {
+        self.parse_chain(Self::parse_not, |p| p.parse_word_operator("AND"))
+    }
+
+    fn parse_not(&mut self) -> ParseResult<Expr> {
+        match self.parse_word_operator("NOT") {
+            Some(op) => Ok(Expr::UnaryOp {
+                op,
+                expr: Box::new(self.nested(Self::parse_not)?),
+            }),
+            None => self.parse_comparison(),
+        }
+    }
+
+    // Parses comparisons and the predicates that share their precedence:
+    // IN, BETWEEN, LIKE and IS
+    fn parse_comparison(&mut self) -> ParseResult<Expr> {
+        let mut expr: Expr = self.parse_other_operators()?;
+
+        // Operators read so far, each wrapping the expression on its left
+        let mut links: usize = 0;
+        loop {
+            self.check_links(links)?;
+            links += 1;
+            let start: usize = self.index;
+            let negated: bool = self.is_keyword("NOT")
+                && ["IN", "BETWEEN", "LIKE", "ILIKE", "SIMILAR"]
+                    .iter()
+                    .any(|keyword| self.is_keyword_at(1, keyword));
+            if negated {
+                self.index += 1;
+            }
+
+            if self.parse_keyword("IN").is_some() {
+                let keyword: Keyword = self.keyword_since(start);
+                self.expect_punct('(')?;
+                expr = if self.starts_query_at(0) {
+                    let subquery: Query = self.parse_query()?;
+                    Expr::InSubquery {
+                        expr: Box::new(expr),
+                        keyword,
+                        subquery: Box::new(subquery),
+                    }
+                } else {
+                    let list: Vec<Expr> = self.parse_comma_separated(Self::parse_expr)?;
+                    Expr::InList {
+                        expr: Box::new(expr),
+                        keyword,
+                        list,
+                    }
+                };
+                self.expect_punct(')')?;
+            } else if self.parse_keyword("BETWEEN").is_some() {
+                let keyword: Keyword = self.keyword_since(start);
+                let low: Expr = self.parse_other_operators()?;
+                self.expect_keyword("AND")?;
+                let high: Expr = self.parse_other_operators()?;
+                expr = Expr::Between {
+                    expr: Box::new(expr),
+                    keyword,
+                    low: Box::new(low),
+                    high: Box::new(high),
+                };
+            } else if let Some(op) = self.parse_pattern_operator(start) {
+                let right: Expr = self.parse_other_operators()?;
+                expr = Self::binary(expr, op, right);
+                if let Some(escape) = self.parse_word_operator("ESCAPE") {
+                    let escape_char: Expr = self.parse_other_operators()?;
+                    expr = Self::binary(expr, escape, escape_char);
+                }
+            } else if self.is_keyword("IS") {
+                expr = self.parse_is(expr)?;
+            } else if let Token::Operator(op) = self.peek()
+                && COMPARISON_OPERATORS.contains(&op.as_str())
+            {
+                let op: Operator = self.parse_symbol_operator();
+                let right: Expr = self.parse_other_operators()?;
+                expr = Self::binary(expr, op, right);
+            } else {
+                self.index = start;
+                return Ok(expr);
+            }
+        }
+    }
+
+    // Reads `[NOT] LIKE`, `[NOT] ILIKE` or `[NOT] SIMILAR TO`, any `NOT`
+    // having already been consumed since `start`
+    fn parse_pattern_operator(&mut self, start: usize) -> Option<Operator> {
+        let found: bool = self.parse_one_of_keywords(&["LIKE", "ILIKE"]).is_some()
+            || self.parse_keywords(&["SIMILAR", "TO"]).is_some();
+        if !found {
+            return None;
+        }
+
+        let keyword: Keyword = self.keyword_since(start);
+        Some(Operator {
+            text: keyword.text,
+            span: keyword.span,
+        })
+    }
+
+    // Parses `IS [NOT] NULL`, `IS [NOT] TRUE` or `IS [NOT] DISTINCT FROM expr`
+    fn parse_is(&mut self, expr: Expr) -> ParseResult<Expr> {
+        let start: usize = self.index;
+        self.expect_keyword("IS")?;
+        self.parse_keyword("NOT");
+
+        if self.parse_keywords(&["DISTINCT", "FROM"]).is_some() {
+            let keyword: Keyword = self.keyword_since(start);
+            let right: Expr = self.parse_other_operators()?;
+            let op: Operator = Operator {
+                text: keyword.text,
+                span: keyword.span,
+            };
+            return Ok(Self::binary(expr, op, right));
+        }
+
+        let keyword: Keyword = self.keyword_since(start);
+        let value: Keyword = self
+            .parse_one_of_keywords(&["NULL", "TRUE", "FALSE", "UNKNOWN"])
+            .ok_or_else(|| self.error("expected NULL, TRUE, FALSE or DISTINCT FROM after IS"))?;
+        let op: Operator = Operator {
+            text: keyword.text,
+            span: keyword.span,
+        };
+        Ok(Self::binary(expr, op, Expr::Keyword(value)))
+    }
+
+    fn parse_symbol_operator(&mut self) -> Operator {
+        let spanned: &SpannedToken = self.advance();
+        match &spanned.token {
+            Token::Operator(op) => Operator {
+                text: op.clone(),
+                span: spanned.span,
+            },
+            _ => unreachable!("caller checked for an operator"),
+        }
+    }
+
+    // Operators without a dedicated precedence level, e.g. `||`, `->>` or `@>`
+    fn parse_other_operators(&mut self) -> ParseResult<Expr> {
+        self.parse_chain(Self::parse_additive, |p| match p.peek() {
+            Token::Operator(op)
+                if !COMPARISON_OPERATORS.contains(&op.as_str())
+                    && !["+", "-", "*", "/", "%", "::", ":", "!", "@"].contains(&op.as_str()) =>
+            {
+                Some(p.parse_symbol_operator())
+            }
+            _ => None,
+        })
+    }
+
+    fn parse_additive(&mut self) -> ParseResult<Expr> {
+        self.parse_chain(Self::parse_multiplicative, |p| {
+            (p.is_operator("+") || p.is_operator("-")).then(|| p.parse_symbol_operator())
+        })
+    }
+
+    fn parse_multiplicative(&mut self) -> ParseResult<Expr> {
+        self.parse_chain(Self::parse_unary, |p| {
+            (p.is_operator("*") || p.is_operator("/") || p.is_operator("%"))
+                .then(|| p.parse_symbol_operator())
+        })
+    }
+
+    fn parse_unary(&mut self) -> ParseResult<Expr> {
+        if self.is_operator("-") || self.is_operator("+") || self.is_operator("~") {
+            let op: Operator = self.parse_symbol_operator();
+            let expr: Expr = self.nested(Self::parse_unary)?;
+            return Ok(Expr::UnaryOp {
+                op,
+                expr: Box::new(expr),
+            });
+        }
+        self.parse_postfix()
+    }
+
+    // Parses a primary expression followed by any `::type` casts, `:path`
+    // accesses and `[subscripts]`
+    fn parse_postfix(&mut self) -> ParseResult<Expr> {
+        let mut expr: Expr = self.parse_primary()?;
+        let mut links: usize = 0;
+        loop {
+            let path_follows: bool = !self.in_subscript
+                && self.is_operator(":")
+                && matches!(
+                    self.peek_nth(1),
+                    Token::Keyword(_) | Token::Identifier(_) | Token::QuotedIdentifier(_)
+                );
+            let subscript_follows: bool = self.is_punct('[');
+            if !self.is_operator("::") && !path_follows && !subscript_follows {
+                return Ok(expr);
+            }
+            self.check_links(links)?;
+            links += 1;
+            if subscript_follows {
+                expr = self.parse_subscript(expr)?;
+                continue;
+            }
+            self.index += 1;
+
+            expr = if path_follows {
+                let mut parts: Vec<Ident> = vec![self.parse_word()?];
+                while self.consume_punct('.') {
+                    parts.push(self.parse_word()?);
+                }
+                Expr::PathAccess {
+                    expr: Box::new(expr),
+                    path: ObjectName(parts),
+                }
+            } else {
+                let data_type: DataType = self.parse_data_type()?;
+                Expr::DoubleColonCast {
+                    expr: Box::new(expr),
+                    data_type: Box::new(data_type),
+                }
+            };
+        }
+    }
+
+    // Parses `[index]` or `[lower:upper]` after `expr`
+    fn parse_subscript(&mut self, expr: Expr) -> ParseResult<Expr> {
+        self.expect_punct('[')?;
+        let in_subscript: bool = std::mem::replace(&mut self.in_subscript, true);
+        let subscript: ParseResult<Subscript> = self.parse_subscript_bounds();
+        self.in_subscript = in_subscript;
+        let subscript: Subscript = subscript?;
+        self.expect_punct(']')?;
+
+        Ok(Expr::Subscript {
+            expr: Box::new(expr),
+            subscript: Box::new(subscript),
+        })
+    }
+
+    fn parse_subscript_bounds(&mut self) -> ParseResult<Subscript> {
+        let lower: Option<Expr> = if self.is_operator(":") || self.is_punct(']') {
+            None
+        } else {
+            Some(self.parse_expr()?)
+        };
+        if !self.is_operator(":") {
+            return lower
+                .map(Subscript::Index)
+                .ok_or_else(|| self.error("expected a subscript"));
+        }
+
+        self.index += 1;
+        let upper: Option<Expr> = if self.is_punct(']') {
+            None
+        } else {
+            Some(self.parse_expr()?)
+        };
+        Ok(Subscript::Slice { lower, upper })
+    }
+
+    // Parses the `[elements]` of an array constructor after any `ARRAY`
+    fn parse_array(&mut self, keyword: Option<Keyword>) -> ParseResult<Expr> {
+        self.expect_punct('[')?;
+        let elements: Vec<Expr> = if self.is_punct(']') {
+            Vec::new()
+        } else {
+            self.parse_comma_separated(Self::parse_expr)?
+        };
+        self.expect_punct(']')?;
+        Ok(Expr::Array { keyword, elements })
+    }
+
+    fn parse_primary(&mut self) -> ParseResult<Expr> {
+        match self.peek() {
+            Token::Literal(value) => {
+                let span: Span = self.advance().span;
+                Ok(Expr::Literal(Literal {
+                    value: value.clone(),
+                    span,
+                }))
+            }
+            Token::DollarQuoted(quoted) => {
+                let value: String = quoted.text();
+                let span: Span = self.advance().span;
+                Ok(Expr::Literal(Literal { value, span }))
+            }
+            Token::Parameter(value) => {
+                let span: Span = self.advance().span;
+                Ok(Expr::Parameter(Literal {
+                    value: value.clone(),
+                    span,
+                }))
+            }
+            Token::Operator(op) if op == "*" => {
+                let span: Span = self.advance().span;
+                Ok(Expr::Wildcard {
+                    qualifier: None,
+                    span,
+                })
+            }
+            Token::Punctuation('(') => self.parse_parenthesized_expr(),
+            Token::Punctuation('[') => self.parse_array(None),
+            Token::QuotedIdentifier(_) => self.parse_name_expr(),
+            Token::Keyword(word) | Token::Identifier(word) => {
+                let upper: String = word.to_uppercase();
+                let next_is_paren: bool = self.peek_nth(1) == &Token::Punctuation('(');
+
+                match upper.as_str() {
+                    "CASE" => self.parse_case(),
+                    "ARRAY" if self.peek_nth(1) == &Token::Punctuation('[') => {
+                        let word: Ident = self.parse_word()?;
+                        self.parse_array(Some(Keyword {
+                            text: word.value,
+                            span: word.span,
+                        }))
+                    }
+                    "EXISTS" if next_is_paren => {
+                        let keyword: Keyword = self.expect_keyword("EXISTS")?;
+                        self.expect_punct('(')?;
+                        let subquery: Query = self.parse_query()?;
+                        self.expect_punct(')')?;
+                        Ok(Expr::Exists {
+                            keyword,
+                            subquery: Box::new(subquery),
+                        })
+                    }
+                    "CAST" | "TRY_CAST" | "SAFE_CAST" if next_is_paren => self.parse_cast(),
+                    _ if VALUE_KEYWORDS.contains(&upper.as_str()) && !next_is_paren => {
+                        let span: Span = self.advance().span;
+                        Ok(Expr::Keyword(Keyword {
+                            text: word.clone(),
+                            span,
+                        }))
+                    }
+                    "DATE" | "TIME" | "TIMESTAMP" | "INTERVAL" if matches!(self.peek_nth(1), Token::Literal(value) if value.starts_with('\'')) =>
+                    {
+                        let name: Ident = self.parse_word()?;
+                        let value: Literal = match self.parse_primary()? {
+                            Expr::Literal(literal) => literal,
+                            _ => unreachable!("checked for a literal"),
+                        };
+                        Ok(Expr::TypedString {
+                            data_type: Box::new(DataType {
+                                name: vec![name],
+                                args: Vec::new(),
+                            }),
+                            value,
+                        })
+                    }
+                    _ if self.is_reserved(&upper)
+                        && !(next_is_paren && FUNCTION_KEYWORDS.contains(&upper.as_str())) =>
+                    {
+                        Err(self.error("unexpected keyword"))
+                    }
+                    _ => self.parse_name_expr(),
+                }
+            }
+            _ => Err(self.error("expected an expression")),
+        }
+    }
+
+    // Parses `(expr)`, a tuple `(a, b)` or a scalar subquery `(SELECT ...)`
+    fn parse_parenthesized_expr(&mut self) -> ParseResult<Expr> {
+        if self.starts_query_at(1) {
+            self.index += 1;
+            let subquery: Query = self.parse_query()?;
+            self.expect_punct(')')?;
+            return Ok(Expr::Subquery(Box::new(subquery)));
+        }
+
+        let mut items: Vec<Expr> = self.parse_parenthesized(Self::parse_expr)?;
+        if items.len() == 1 {
+            Ok(Expr::Nested(Box::new(items.remove(0))))
+        } else {
+            Ok(Expr::Tuple(items))
+        }
+    }
+
+    // Parses a column reference, a `table.*` wildcard or a function call
+    fn parse_name_expr(&mut self) -> ParseResult<Expr> {
+        let mut parts: Vec<Ident> = vec![self.parse_word()?];
+
+        while self.is_punct('.') {
+            if matches!(self.peek_nth(1), Token::Operator(op) if op == "*") {
+                self.index += 1;
+                let span: Span = self.advance().span;
+                return Ok(Expr::Wildcard {
+                    qualifier: Some(ObjectName(parts)),
+                    span,
+                });
+            }
+            self.index += 1;
+            parts.push(self.parse_word()?);
+        }
+
+        let name: ObjectName = ObjectName(parts);
+        if self.is_punct('(') {
+            return Ok(Expr::Function(Box::new(self.parse_function(name)?)));
+        }
+        Ok(Expr::Identifier(name))
+    }
+
+    fn parse_function(&mut self, name: ObjectName) -> ParseResult<Function> {
+        self.expect_punct('(')?;
+
+        // Arguments in a syntax we do not model, e.g. `EXTRACT(YEAR FROM d)`,
+        // are kept as tokens rather than failing the whole statement
+        let checkpoint: usize = self.index;
+        let args: FunctionArgs = match self.parse_function_args() {
+            Ok(args) if self.is_punct(')') => args,
+            _ => {
+                self.index = checkpoint;
+                FunctionArgs::Raw(self.parse_raw_until_close())
+            }
+        };
+        self.expect_punct(')')?;
+
+        let filter: Option<Box<Expr>> =
+            if self.is_keyword("FILTER") && self.peek_nth(1) == &Token::Punctuation('(') {
+                self.index += 2;
+                self.expect_keyword("WHERE")?;
+                let condition: Expr = self.parse_expr()?;
+                self.expect_punct(')')?;
+                Some(Box::new(condition))
+            } else {
+                None
+            };
+
+        let over: Option<WindowSpec> = if self.parse_keyword("OVER").is_some() {
+            if self.consume_punct('(') {
+                Some(self.parse_window_spec()?)
+            } else {
+                Some(WindowSpec::Named(self.parse_identifier()?))
+            }
+        } else {
+            None
+        };
+
+        Ok(Function {
+            name,
+            args,
+            filter,
+            over,
+        })
+    }
+
+    fn parse_function_args(&mut self) -> ParseResult<FunctionArgs> {
+        let quantifier: Option<Keyword> = self.parse_one_of_keywords(&["DISTINCT", "ALL"]);
+        let args: Vec<Expr> = if self.is_punct(')') {
+            Vec::new()
+        } else {
+            self.parse_comma_separated(|parser| {
+                if parser.starts_query_at(0) && !parser.is_punct('(') {
+                    Ok(Expr::Subquery(Box::new(parser.parse_query()?)))
+                } else {
+                    parser.parse_expr()
+                }
+            })?
+        };
+        let order_by: Vec<OrderByExpr> = match self.parse_keywords(&["ORDER", "BY"]) {
+            Some(_) => self.parse_comma_separated(Self::parse_order_by_expr)?,
+            None => Vec::new(),
+        };
+
+        Ok(FunctionArgs::List {
+            quantifier,
+            args,
+            order_by,
+        })
+    }
+
+    // Parses the inside of `OVER (...)`, the opening parenthesis already consumed
+    fn parse_window_spec(&mut self) -> ParseResult<WindowSpec> {
+        let partition_by: Vec<Expr> = match self.parse_keywords(&["PARTITION", "BY"]) {
+            Some(_) => self.parse_comma_separated(Self::parse_expr)?,
+            None => Vec::new(),
+        };
+        let order_by: Vec<OrderByExpr> = match self.parse_keywords(&["ORDER", "BY"]) {
+            Some(_) => self.parse_comma_separated(Self::parse_order_by_expr)?,
+            None => Vec::new(),
+        };
+        let frame: Vec<SpannedToken> = self.parse_raw_until_list_end();
+        self.expect_punct(')')?;
+
+        Ok(WindowSpec::Inline {
+            partition_by,
+            order_by,
+            frame,
+        })
+    }
+
+    fn parse_case(&mut self) -> ParseResult<Expr> {
+        let keyword: Keyword = self.expect_keyword("CASE")?;
+        let operand: Option<Box<Expr>> = if self.is_keyword("WHEN") {
+            None
+        } else {
+            Some(Box::new(self.parse_expr()?))
+        };
+
+        let mut conditions: Vec<WhenClause> = Vec::new();
+        while let Some(when) = self.parse_keyword("WHEN") {
+            let condition: Expr = self.parse_expr()?;
+            let then: Keyword = self.expect_keyword("THEN")?;
+            let result: Expr = self.parse_expr()?;
+            conditions.push(WhenClause {
+                when,
+                condition,
+                then,
+                result,
+            });
+        }
+        if conditions.is_empty() {
+            return Err(self.error("expected WHEN"));
+        }
+
+        let else_result: Option<Clause<Expr>> = self.parse_clause(&["ELSE"], Self::parse_expr)?;
+        let end: Keyword = self.expect_keyword("END")?;
+
+        Ok(Expr::Case(Box::new(Case {
+            keyword,
+            operand,
+            conditions,
+            else_result,
+            end,
+        })))
+    }
+
+    fn parse_cast(&mut self) -> ParseResult<Expr> {
+        let word: Ident = self.parse_word()?;
+        let keyword: Keyword = Keyword {
+            text: word.value,
+            span: word.span,
+        };
+        self.expect_punct('(')?;
+        let expr: Expr = self.parse_expr()?;
+        let as_keyword: Keyword = self.expect_keyword("AS")?;
+        let data_type: DataType = self.parse_data_type()?;
+        self.expect_punct(')')?;
+
+        Ok(Expr::Cast {
+            keyword,
+            expr: Box::new(expr),
+            as_keyword,
+            data_type: Box::new(data_type),
+        })
+    }
+
+    // Parses a type name such as `int`, `double precision`,
+    // `character varying(255)` or `time with time zone`
+    fn parse_data_type(&mut self) -> ParseResult<DataType> {
+        let mut name: Vec<Ident> = vec![self.parse_word()?];
+
+        loop {
+            let words: usize = if ["PRECISION", "VARYING", "UNSIGNED"]
+                .iter()
+                .any(|word| self.is_keyword(word))
+            {
+                1
+            } else if (self.is_keyword("WITH") || self.is_keyword("WITHOUT"))
+                && self.is_keyword_at(1, "TIME")
+                && self.is_keyword_at(2, "ZONE")
+            {
+                3
+            } else {
+                break;
+            };
+
+            for _ in 0..words {
+                name.push(self.parse_word()?);
+            }
+        }
+
+        let args: Vec<Expr> = if self.is_punct('(') {
+            self.parse_parenthesized(Self::parse_expr)?
+        } else {
+            Vec::new()
+        };
+        Ok(DataType { name, args })
+    }
+}
@@ -0,0 +1,179 @@
+use crate::lexer::{SpannedToken, Token};
+
+// Splits a token stream into statements at top level semicolons. Strings,
+// comments and dollar quoted bodies are single tokens already, so only
+// procedural blocks need tracking: a `;` between `BEGIN` and its `END` belongs
+// to the enclosing statement (e.g. a `CREATE PROCEDURE` body). Outside of
+// procedural code, `BEGIN` starts a transaction instead. A batch
+// separator such as `GO` on a line of its own also ends a statement, and so
+// does source that could not be tokenized, as it runs to the end of its line.
+pub struct StatementSplitter<'a> {
+    tokens: &'a [SpannedToken],
+    position: usize,
+    batch_separator: Option<&'static str>,
+}
+
+// One statement's tokens, without its terminating semicolon
+#[derive(Debug, PartialEq, Clone)]
+pub struct SplitStatement<'a> {
+    pub tokens: &'a [SpannedToken],
+    pub terminated: bool,
+    // The batch separator ending the statement, if any
+    pub batch_separator: Option<&'a SpannedToken>,
+}
+
+impl<'a> StatementSplitter<'a> {
+    pub fn new(tokens: &'a [SpannedToken], batch_separator: Option<&'static str>) -> Self {
+        StatementSplitter {
+            tokens,
+            position: 0,
+            batch_separator,
+        }
+    }
+
+    // Checks whether the word at `index` is the batch separator standing on a
+    // line of its own
+    fn is_batch_separator(&self, index: usize, word: &str) -> bool {
+        let Some(separator) = self.batch_separator else {
+            return false;
+        };
+        let line: usize = self.tokens[index].span.start.line;
+        let significant = |spanned: &&SpannedToken| {
+            !matches!(spanned.token, Token::Whitespace | Token::Comment(_))
+        };
+
+        word.eq_ignore_ascii_case(separator)
+            && self.tokens[..index]
+                .iter()
+                .rev()
+                .find(significant)
+                .is_none_or(|previous| previous.span.end.line < line)
+            && self.tokens[index + 1..]
+                .iter()
+                .find(significant)
+                .is_none_or(|next| next.span.start.line > line)
+    }
+
+    // Upper cased word at `index`, skipping whitespace and comments, if the
+    // token there is a keyword or plain identifier
+    fn word_after(&self, index: usize) -> Option<String> {
+        self.tokens[index..]
+            .iter()
+            .find(|spanned| !matches!(spanned.token, Token::Whitespace | Token::Comment(_)))
+            .and_then(|spanned| match &spanned.token {
+                Token::Keyword(word) | Token::Identifier(word) => Some(word.to_uppercase()),
+                _ => None,
+            })
+    }
+
+    // Checks whether the `BEGIN` at `index` opens a block rather than a
+    // transaction (`BEGIN;`, `BEGIN TRANSACTION`, `BEGIN WORK`)
+    fn opens_block(&self, index: usize) -> bool {
+        let next: Option<&SpannedToken> = self.tokens[index + 1..]
+            .iter()
+            .find(|spanned| !matches!(spanned.token, Token::Whitespace | Token::Comment(_)));
+
+        match next {
+            None => false,
+            Some(spanned) if spanned.token == Token::Punctuation(';') => false,
+            Some(_) => !matches!(
+                self.word_after(index + 1).as_deref(),
+                Some("TRANSACTION" | "WORK" | "TRAN" | "DEFERRED" | "IMMEDIATE" | "EXCLUSIVE")
+            ),
+        }
+    }
+}
+
+impl<'a> Iterator for StatementSplitter<'a> {
+    type Item = SplitStatement<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.position >= self.tokens.len() {
+            return None;
+        }
+
+        let start: usize = self.position;
+        // Open `BEGIN` and `CASE` blocks, each closed by an `END`
+        let mut depth: usize = 0;
+        let mut previous_word: Option<String> = None;
+        let mut first_word: Option<String> = None;
+        // Whether the statement holds procedural code, as the body of a
+        // procedure, function or trigger or as a `DO` block
+        let mut procedural: bool = false;
+
+        for index in start..self.tokens.len() {
+            let token: &Token = &self.tokens[index].token;
+            match token {
+                Token::Punctuation(';') if depth == 0 => {
+                    self.position = index + 1;
+                    return Some(SplitStatement {
+                        tokens: &self.tokens[start..index],
+                        terminated: true,
+                        batch_separator: None,
+                    });
+                }
+                Token::Invalid(_) => {
+                    self.position = index + 1;
+                    return Some(SplitStatement {
+                        tokens: &self.tokens[start..=index],
+                        terminated: false,
+                        batch_separator: None,
+                    });
+                }
+                Token::Keyword(word) | Token::Identifier(word)
+                    if depth == 0 && self.is_batch_separator(index, word) =>
+                {
+                    self.position = index + 1;
+                    return Some(SplitStatement {
+                        tokens: &self.tokens[start..index],
+                        terminated: false,
+                        batch_separator: Some(&self.tokens[index]),
+                    });
+                }
+                Token::Keyword(word) | Token::Identifier(word) => {
+                    let word: String = word.to_uppercase();
+                    let after_end: bool = previous_word.as_deref() == Some("END");
+                    let first: &str = first_word.get_or_insert_with(|| word.clone());
+                    let defines_routine: bool = matches!(first, "CREATE" | "ALTER");
+
+                    match word.as_str() {
+                        "DO" if first == "DO" => procedural = true,
+                        "PROCEDURE" | "PROC" | "FUNCTION" | "TRIGGER" if defines_routine => {
+                            procedural = true
+                        }
+                        "BEGIN"
+                            if (procedural
+                                || depth > 0
+                                || previous_word.as_deref() == Some("AS"))
+                                && self.opens_block(index) =>
+                        {
+                            depth += 1
+                        }
+                        "CASE" if !after_end => depth += 1,
+                        // `END IF`, `END LOOP`, ... close blocks that were never counted
+                        "END" => {
+                            let closes_uncounted: bool = matches!(
+                                self.word_after(index + 1).as_deref(),
+                                Some("IF" | "LOOP" | "WHILE" | "REPEAT" | "FOR")
+                            );
+                            if !closes_uncounted {
+                                depth = depth.saturating_sub(1);
+                            }
+                        }
+                        _ => {}
+                    }
+                    previous_word = Some(word);
+                }
+                Token::Whitespace | Token::Comment(_) => {}
+                _ => previous_word = None,
+            }
+        }
+
+        self.position = self.tokens.len();
+        Some(SplitStatement {
+            tokens: &self.tokens[start..],
+            terminated: false,
+            batch_separator: None,
+        })
+    }
+}
@@ -0,0 +1,156 @@
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Output, Stdio};
+
+// Runs the binary in `dir` with `args`, feeding it `stdin`
+fn run(dir: &Path, args: &[&str], stdin: &str) -> Output {
+    let mut child: Child = Command::new(env!("CARGO_BIN_EXE_sql-formatter"))
+        .args(args)
+        .current_dir(dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+// An empty directory of its own for the test called `name`
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir: PathBuf =
+        std::env::temp_dir().join(format!("sql-formatter-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn prints_only_the_sql_when_piped() {
+    let dir: PathBuf = scratch_dir("piped");
+    let output: Output = run(&dir, &[], "select a from t");
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "SELECT a\nFROM t\n");
+    assert_eq!(stderr(&output), "");
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn formats_files_into_the_output_file() {
+    let dir: PathBuf = scratch_dir("output");
+    fs::write(dir.join("a.sql"), "select 1").unwrap();
+    fs::write(dir.join("b.sql"), "select 2").unwrap();
+
+    let output: Output = run(&dir, &["a.sql", "b.sql", "-o", "out.sql"], "");
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "");
+    assert_eq!(
+        fs::read_to_string(dir.join("out.sql")).unwrap(),
+        "SELECT 1\nSELECT 2\n"
+    );
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn fails_on_input_it_cannot_format() {
+    let dir: PathBuf = scratch_dir("failure");
+    let output: Output = run(&dir, &["--stdin-filename", "query.sql"], "select 'a");
+    assert!(!output.status.success());
+    assert!(stderr(&output).starts_with("Error: query.sql:"));
+
+    let output: Output = run(&dir, &["missing.sql"], "");
+    assert!(!output.status.success());
+    assert!(stderr(&output).starts_with("Error: missing.sql:"));
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn writes_files_in_place_and_reports_the_changed_ones() {
+    let dir: PathBuf = scratch_dir("write");
+    fs::create_dir_all(dir.join("queries")).unwrap();
+    fs::write(dir.join("queries/a.sql"), "select 1").unwrap();
+    fs::write(dir.join("queries/b.sql"), "SELECT 2\n").unwrap();
+    fs::write(dir.join("queries/notes.txt"), "select 3").unwrap();
+
+    let output: Output = run(&dir, &["--write", "queries"], "");
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        format!(
+            "Formatted {}\n1 file(s) reformatted, 1 unchanged\n",
+            Path::new("queries").join("a.sql").display()
+        )
+    );
+    assert_eq!(
+        fs::read_to_string(dir.join("queries/a.sql")).unwrap(),
+        "SELECT 1\n"
+    );
+    assert_eq!(
+        fs::read_to_string(dir.join("queries/notes.txt")).unwrap(),
+        "select 3"
+    );
+
+    // A file that cannot be formatted is left as it is
+    fs::write(dir.join("queries/c.sql"), "select 'a").unwrap();
+    let output: Output = run(&dir, &["--write", "-q", "queries/c.sql"], "");
+    assert!(!output.status.success());
+    assert_eq!(
+        fs::read_to_string(dir.join("queries/c.sql")).unwrap(),
+        "select 'a"
+    );
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn checks_files_and_prints_a_diff_for_unformatted_ones() {
+    let dir: PathBuf = scratch_dir("check");
+    fs::write(dir.join("a.sql"), "SELECT 1\n").unwrap();
+    let output: Output = run(&dir, &["--check", "a.sql"], "");
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "");
+
+    fs::write(dir.join("b.sql"), "select 1\n").unwrap();
+    let output: Output = run(&dir, &["--check", "--color", "never", "a.sql", "b.sql"], "");
+    assert!(!output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "--- b.sql (original)\n+++ b.sql (formatted)\n@@ -1 +1 @@\n-select 1\n+SELECT 1\n"
+    );
+    assert_eq!(stderr(&output), "1 file(s) would be reformatted\n");
+    assert_eq!(fs::read_to_string(dir.join("b.sql")).unwrap(), "select 1\n");
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn lets_the_command_line_turn_off_what_a_config_turns_on() {
+    let dir: PathBuf = scratch_dir("flags");
+    fs::write(dir.join("sqlformat.toml"), "inline_short_case = true").unwrap();
+    let sql: &str = "select case when a then 1 end";
+
+    let output: Output = run(&dir, &[], sql);
+    assert_eq!(stdout(&output), "SELECT CASE WHEN a THEN 1 END\n");
+
+    let output: Output = run(&dir, &["--inline-short-case=false"], sql);
+    assert_eq!(
+        stdout(&output),
+        "SELECT\n\tCASE\n\t\tWHEN a THEN 1\n\tEND\n"
+    );
+
+    let output: Output = run(&dir, &["--indent-width", "0"], sql);
+    assert!(!output.status.success());
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,455 @@
+use sql_formatter::{
+    CaseStyle, CommaPosition, FormatError, FormatOptions, IndentStyle, SqlDialect, format,
+    format_recovering,
+};
+
+// Formats `sql` with the default options changed by `configure`
+fn format_with(sql: &str, configure: impl FnOnce(&mut FormatOptions)) -> String {
+    let mut options: FormatOptions = FormatOptions::default();
+    configure(&mut options);
+    format(sql, &options).unwrap()
+}
+
+fn format_default(sql: &str) -> String {
+    format_with(sql, |_| {})
+}
+
+fn format_in(sql: &str, dialect: SqlDialect) -> String {
+    format_with(sql, |options| options.dialect = dialect)
+}
+
+// Formatting formatted SQL again leaves it as it is
+fn assert_stable(sql: &str, options: &FormatOptions) {
+    let once: String = format(sql, options).unwrap();
+    assert_eq!(format(&once, options).unwrap(), once);
+}
+
+#[test]
+fn keeps_comments() {
+    assert_eq!(
+        format_default("/* header */\nselect a -- first\n, b from t"),
+        "/* header */\nSELECT\n\ta, -- first\n\tb\nFROM t"
+    );
+}
+
+#[test]
+fn keeps_comments_beside_keywords() {
+    assert_eq!(
+        format_default("select a from t join u -- why\n on t.id = u.id"),
+        "SELECT a\nFROM\n\tt\n\tJOIN u -- why\n\tON t.id = u.id"
+    );
+    assert_eq!(
+        format_default("select case when a = 1 -- one\nthen 'x' else 'y' end from t"),
+        "SELECT\n\tCASE\n\t\tWHEN a = 1 -- one\n\t\tTHEN 'x'\n\t\tELSE 'y'\n\tEND\nFROM t"
+    );
+}
+
+#[test]
+fn keeps_one_line_comment_per_line_beside_the_code_it_follows() {
+    let sql: &str = "create table t (id int -- c7\n not null -- c8\n, b int);\n\
+        select a -- c7\n, -- c8\nb from t where x = 1 and -- c6\n y = 2 and z = 3";
+    let formatted: String = format_default(sql);
+    assert_eq!(
+        formatted,
+        "CREATE TABLE t (\n\tid int -- c7\n\tNOT NULL, -- c8\n\tb int\n);\n\n\
+        SELECT\n\ta, -- c7\n\t-- c8\n\tb\nFROM t\n\
+        WHERE\n\tx = 1\n\tAND -- c6\n\ty = 2\n\tAND z = 3"
+    );
+    assert_eq!(format_default(&formatted), formatted);
+    assert_stable(
+        "select a::int -- c7\n:: text -- c8\nfrom t",
+        &FormatOptions::default(),
+    );
+}
+
+#[test]
+fn cases_data_types_in_unparsed_statements() {
+    let formatted: String = format_with(
+        "alter table foo add column bar int default 0::bigint;\n\
+        alter table foo alter column date type timestamp with time zone",
+        |options| {
+            options.dialect = SqlDialect::Postgres;
+            options.data_type_case = CaseStyle::Upper;
+        },
+    );
+    assert_eq!(
+        formatted,
+        "ALTER TABLE foo ADD COLUMN bar INT DEFAULT 0::BIGINT;\n\n\
+        ALTER TABLE foo ALTER COLUMN date TYPE TIMESTAMP WITH TIME ZONE"
+    );
+}
+
+#[test]
+fn measures_text_spanning_lines_up_to_its_first_line_break() {
+    let sql: &str = "create function f() returns table (x int) as $$\nbegin\n  return;\nend\n$$ language plpgsql";
+    assert_eq!(
+        format_in(sql, SqlDialect::Postgres),
+        "CREATE FUNCTION f() RETURNS TABLE (x int) AS $$\nbegin\n  return;\nend\n$$ LANGUAGE plpgsql"
+    );
+    assert_eq!(
+        format_default("select a, 'line1\nline2' from t"),
+        "SELECT a, 'line1\nline2'\nFROM t"
+    );
+}
+
+#[test]
+fn spaces_inline_block_comments() {
+    assert_eq!(
+        format_default("select a  /* c */+ b from t"),
+        "SELECT a /* c */ + b\nFROM t"
+    );
+}
+
+#[test]
+fn keeps_quoted_identifiers_and_literals_as_written() {
+    assert_eq!(
+        format_with(r#"select "Mixed", x as 'total' from T"#, |options| {
+            options.dialect = SqlDialect::Mysql;
+            options.identifier_case = CaseStyle::Upper;
+        }),
+        "SELECT \"Mixed\", X AS 'total'\nFROM T"
+    );
+}
+
+#[test]
+fn reports_unterminated_strings() {
+    let err: FormatError = format("select 'abc", &FormatOptions::default()).unwrap_err();
+    assert_eq!(
+        err.render("query.sql", "select 'abc"),
+        "unterminated string literal\n --> query.sql:1:8\n  |\n1 | select 'abc\n  |        ^^^^"
+    );
+}
+
+#[test]
+fn recovers_from_lex_errors() {
+    let (formatted, errors): (String, Vec<FormatError>) =
+        format_recovering("select 'abc\nfrom t;\nselect 1", &FormatOptions::default());
+    assert_eq!(errors.len(), 1);
+    assert!(formatted.contains("'abc"));
+    assert!(formatted.ends_with("SELECT 1"));
+}
+
+#[test]
+fn lays_out_clauses_and_joins() {
+    assert_eq!(
+        format_default(
+            "select a from t left outer join u on t.id = u.id \
+             where a is not null group by a order by a desc nulls last"
+        ),
+        "SELECT a\nFROM\n\tt\n\tLEFT OUTER JOIN u ON t.id = u.id\nWHERE a IS NOT NULL\n\
+         GROUP BY a\nORDER BY a DESC NULLS LAST"
+    );
+}
+
+#[test]
+fn separates_statements() {
+    assert_eq!(
+        format_default("select 1; select 2"),
+        "SELECT 1;\n\nSELECT 2"
+    );
+    assert_eq!(
+        format_with("select 1; select 2", |options| {
+            options.lines_between_statements = 0;
+        }),
+        "SELECT 1;\nSELECT 2"
+    );
+}
+
+#[test]
+fn begin_starts_a_transaction_outside_procedures() {
+    assert_eq!(
+        format_default("BEGIN ISOLATION LEVEL SERIALIZABLE; select 1; commit;"),
+        "BEGIN ISOLATION LEVEL SERIALIZABLE;\n\nSELECT 1;\n\nCOMMIT;"
+    );
+}
+
+#[test]
+fn keeps_procedure_bodies_in_one_statement() {
+    let formatted: String = format_in(
+        "create procedure p() begin select 1; select 2; end; select 3",
+        SqlDialect::Mysql,
+    );
+    assert!(!formatted.contains("1;\n\n"), "{formatted}");
+    assert!(formatted.ends_with("END;\n\nSELECT 3"), "{formatted}");
+}
+
+#[test]
+fn indents_with_spaces() {
+    assert_eq!(
+        format_with("select a from t join u on true", |options| {
+            options.indent_style = IndentStyle::Spaces;
+            options.indent_width = 2;
+        }),
+        "SELECT a\nFROM\n  t\n  JOIN u ON TRUE"
+    );
+}
+
+#[test]
+fn applies_case_options() {
+    assert_eq!(
+        format_with("SELECT Count(X) FROM T WHERE A IS NULL", |options| {
+            options.keyword_case = CaseStyle::Capitalize;
+            options.function_case = CaseStyle::Lower;
+            options.identifier_case = CaseStyle::Lower;
+        }),
+        "Select count(x)\nFrom t\nWhere a Is Null"
+    );
+}
+
+#[test]
+fn wraps_long_lists() {
+    let sql: &str = "select aaaaaaaaaaaaaaaaaaaa, bbbbbbbbbbbbbbbbbbbb, cccccccccccccccccccc, \
+                     dddddddddddddddddddd from t";
+    assert_eq!(
+        format_default(sql),
+        "SELECT\n\taaaaaaaaaaaaaaaaaaaa,\n\tbbbbbbbbbbbbbbbbbbbb,\n\tcccccccccccccccccccc,\n\
+         \tdddddddddddddddddddd\nFROM t"
+    );
+    assert_eq!(
+        format_with(sql, |options| {
+            options.comma_position = CommaPosition::Leading;
+        }),
+        "SELECT\n\taaaaaaaaaaaaaaaaaaaa\n\t, bbbbbbbbbbbbbbbbbbbb\n\t, cccccccccccccccccccc\n\
+         \t, dddddddddddddddddddd\nFROM t"
+    );
+}
+
+#[test]
+fn keeps_name_keywords_as_identifiers() {
+    assert_eq!(
+        format_with("update data set value = 1 where id = 2", |options| {
+            options.dialect = SqlDialect::Mysql;
+        }),
+        "UPDATE data\nSET value = 1\nWHERE id = 2"
+    );
+}
+
+#[test]
+fn parses_function_like_keywords() {
+    assert_eq!(
+        format_default("select left(name, 2), right(name, 1) from t"),
+        "SELECT left(name, 2), right(name, 1)\nFROM t"
+    );
+}
+
+#[test]
+fn survives_deep_nesting() {
+    let sql: String = format!("select {}1{}", "(".repeat(500), ")".repeat(500));
+    let formatted: String = format_default(&sql);
+    assert_eq!(formatted.matches('(').count(), 500);
+}
+
+#[test]
+fn survives_long_operator_chains() {
+    let sum: String = format!("select a{}", " + a".repeat(20000));
+    assert_eq!(format_default(&sum).matches('+').count(), 20000);
+
+    let conditions: String = format!("select a from t where x = 1{}", " and x = 1".repeat(20000));
+    assert_eq!(format_default(&conditions).matches("AND").count(), 20000);
+
+    let comparisons: String = format!("select a{}", " = a".repeat(1000));
+    assert_eq!(format_default(&comparisons).matches('=').count(), 1000);
+}
+
+#[test]
+fn sql_server_temp_tables_national_strings_and_batches() {
+    assert_eq!(
+        format_in(
+            "select * from #tmp where name = N'abc' and id = @id\nGO\nselect 1",
+            SqlDialect::SqlServer
+        ),
+        "SELECT *\nFROM #tmp\nWHERE name = N'abc' AND id = @id\nGO\n\nSELECT 1"
+    );
+}
+
+#[test]
+fn snowflake_stages_and_paths() {
+    assert_eq!(
+        format_in(
+            "select payload:id, payload:customer.name::string from @my_stage/path/ where v:Key = 1",
+            SqlDialect::Snowflake
+        ),
+        "SELECT payload:id, payload:customer.name::string\nFROM @my_stage/path/\nWHERE v:Key = 1"
+    );
+    assert_eq!(
+        format_in("list @stage/dir;", SqlDialect::Snowflake),
+        "list @stage/dir;"
+    );
+}
+
+#[test]
+fn keeps_subscripts_and_arrays_tight() {
+    assert_eq!(
+        format_in(
+            "select array[1,2], arr[1], a[2:3], f(x)[1][2] from t",
+            SqlDialect::Postgres
+        ),
+        "SELECT ARRAY[1, 2], arr[1], a[2:3], f(x)[1][2]\nFROM t"
+    );
+    assert_eq!(
+        format_default("select arr[1], [b] from t"),
+        "SELECT arr[1], [b]\nFROM t"
+    );
+    assert_eq!(
+        format_in(
+            "alter table t add column c int[] default array[1,2]",
+            SqlDialect::Postgres
+        ),
+        "ALTER TABLE t ADD COLUMN c int[] DEFAULT ARRAY[1, 2]"
+    );
+}
+
+#[test]
+fn keeps_bind_parameters() {
+    assert_eq!(
+        format_in(
+            "select a from t where id = $1 and b = :name",
+            SqlDialect::Postgres
+        ),
+        "SELECT a\nFROM t\nWHERE id = $1 AND b = :name"
+    );
+}
+
+#[test]
+fn formats_sql_function_bodies() {
+    let format_body = |sql: &str| {
+        format_with(sql, |options| {
+            options.dialect = SqlDialect::Postgres;
+            options.format_function_bodies = true;
+        })
+    };
+    assert_eq!(
+        format_body(
+            "create function f() returns int as $$ select 1 from t where a=1 $$ language sql;"
+        ),
+        "CREATE FUNCTION f() RETURNS int AS $$\n\tSELECT 1\n\tFROM t\n\tWHERE a = 1\n$$ LANGUAGE SQL;"
+    );
+    assert_eq!(
+        format_body(
+            "create function f() returns int as $$ begin return 1; end $$ language plpgsql;"
+        ),
+        "CREATE FUNCTION f() RETURNS int AS $$ begin return 1; end $$ LANGUAGE plpgsql;"
+    );
+}
+
+#[test]
+fn lays_out_case_expressions() {
+    let sql: &str = "select case when a = 1 then 'one' else 'many' end as n from t";
+    assert_eq!(
+        format_default(sql),
+        "SELECT\n\tCASE\n\t\tWHEN a = 1 THEN 'one'\n\t\tELSE 'many'\n\tEND AS n\nFROM t"
+    );
+    assert_eq!(
+        format_with(sql, |options| options.inline_short_case = true),
+        "SELECT CASE WHEN a = 1 THEN 'one' ELSE 'many' END AS n\nFROM t"
+    );
+}
+
+#[test]
+fn lays_out_row_locks_distinct_on_and_upserts() {
+    assert_eq!(
+        format_in(
+            "select distinct on (a) a, b from t where id = 1 for update of t skip locked",
+            SqlDialect::Postgres
+        ),
+        "SELECT DISTINCT ON (a) a, b\nFROM t\nWHERE id = 1\nFOR UPDATE OF t SKIP LOCKED"
+    );
+    assert_eq!(
+        format_in(
+            "insert into t (a, b) values (1, 2) on conflict (a) do update set b = excluded.b \
+             where t.b <> excluded.b returning a",
+            SqlDialect::Postgres
+        ),
+        "INSERT INTO t (a, b)\nVALUES (1, 2)\nON CONFLICT (a) DO UPDATE\n\tSET b = excluded.b\n\
+         \tWHERE t.b <> excluded.b\nRETURNING a"
+    );
+    assert_eq!(
+        format_in(
+            "insert into t (a) values (1) on conflict on constraint t_pkey do nothing",
+            SqlDialect::Postgres
+        ),
+        "INSERT INTO t (a)\nVALUES (1)\nON CONFLICT ON CONSTRAINT t_pkey DO NOTHING"
+    );
+}
+
+#[test]
+fn lays_out_statements_the_parser_does_not_cover() {
+    assert_eq!(
+        format_in(
+            "merge into t using s on t.id = s.id when matched then update set a = s.a \
+             when not matched then insert (id, a) values (s.id, s.a)",
+            SqlDialect::Postgres
+        ),
+        "MERGE INTO t\nUSING s ON t.id = s.id\nWHEN MATCHED THEN UPDATE SET a = s.a\n\
+         WHEN NOT MATCHED THEN INSERT (id, a) VALUES (s.id, s.a)"
+    );
+    assert_eq!(
+        format_default("grant select, insert on t to bob; revoke select on t from bob"),
+        "GRANT SELECT, INSERT ON t TO bob;\n\nREVOKE SELECT ON t FROM bob"
+    );
+    assert_eq!(
+        format_in(
+            "alter table t alter column a set data type text",
+            SqlDialect::Postgres
+        ),
+        "ALTER TABLE t ALTER COLUMN a SET DATA TYPE text"
+    );
+}
+
+#[test]
+fn cases_keywords_that_continue_a_keyword_phrase() {
+    assert_eq!(
+        format_in(
+            "merge into t using s on t.id = s.id when matched then delete \
+             when not matched then insert values (s.id)",
+            SqlDialect::Postgres
+        ),
+        "MERGE INTO t\nUSING s ON t.id = s.id\nWHEN MATCHED THEN DELETE\n\
+         WHEN NOT MATCHED THEN INSERT VALUES (s.id)"
+    );
+    assert_eq!(
+        format_in(
+            "alter table t alter column id add generated always as identity",
+            SqlDialect::Postgres
+        ),
+        "ALTER TABLE t ALTER COLUMN id ADD GENERATED ALWAYS AS IDENTITY"
+    );
+}
+
+#[test]
+fn lays_out_unparsed_queries_like_parsed_ones() {
+    // MySQL's `LOCK IN SHARE MODE` keeps the parser from reading the query
+    assert_eq!(
+        format_in(
+            "select case when a = 1 then 'x' else 'y' end as c from t \
+             left join u on t.id = u.id join v using (id) where a = 1 and b = 2 \
+             lock in share mode",
+            SqlDialect::Mysql
+        ),
+        "SELECT\n\tCASE\n\t\tWHEN a = 1 THEN 'x'\n\t\tELSE 'y'\n\tEND AS c\nFROM\n\tt\n\
+         \tLEFT JOIN u ON t.id = u.id\n\tJOIN v USING (id)\nWHERE a = 1 AND b = 2 LOCK IN SHARE MODE"
+    );
+}
+
+#[test]
+fn nests_subqueries() {
+    assert_eq!(
+        format_default("select * from (select a from t) as s where a > (select max(a) from u)"),
+        "SELECT *\nFROM\n\t(\n\t\tSELECT a\n\t\tFROM t\n\t) AS s\nWHERE\n\ta > (\n\
+         \t\tSELECT max(a)\n\t\tFROM u\n\t)"
+    );
+}
+
+#[test]
+fn formatting_is_stable() {
+    let options: FormatOptions = FormatOptions::default();
+    for sql in [
+        "select a, b from t where x = 1 and y = 2 -- trailing\n;",
+        "insert into t (a, b) values (1, 2), (3, 4)",
+        "select a from t union all select b from u",
+        "select * from (select a from t) as s where a in (select id from u)",
+        "with c as (select 1) select * from c join d using (id)",
+    ] {
+        assert_stable(sql, &options);
+    }
+}